@@ -5,4 +5,8 @@
 # rustup install nightly
 # cargo install cargo-fuzz
 
-RUST_BACKTRACE=1 cargo +nightly fuzz run shell_link_header
+# the fuzz target can be selected with the first argument,
+# which is one of shell_link_header and shell_link
+TARGET=${1:-shell_link}
+
+RUST_BACKTRACE=1 cargo +nightly fuzz run "$TARGET"
@@ -0,0 +1,16 @@
+#![no_main]
+
+use encoding_rs::WINDOWS_1252;
+use libfuzzer_sys::fuzz_target;
+use lnk::{ParseOptions, ShellLink};
+
+fuzz_target!(|data: &[u8]| {
+    for options in [ParseOptions::default(), ParseOptions::lenient()] {
+        if let Ok(shell_link) = ShellLink::from_bytes_with_options(data, WINDOWS_1252, options) {
+            // the query API must not panic on any link which could be parsed
+            let _ = shell_link.link_target();
+            let _ = shell_link.id_list_target();
+            let _ = format!("{shell_link:?}");
+        }
+    }
+});
@@ -1,13 +1,14 @@
 #![no_main]
 
 use libfuzzer_sys::fuzz_target;
-use lnk::ShellLinkHeader;
+use lnk::{ParseContext, ShellLinkHeader};
 use std::io::Cursor;
 use binrw::BinReaderExt;
 
 fuzz_target!(|data: &[u8]| {
     let mut cursor = Cursor::new(data);
-    match cursor.read_le::<ShellLinkHeader>() {
+    let ctx = ParseContext::default();
+    match cursor.read_le_args::<ShellLinkHeader>((&ctx,)) {
         Err(_) => (),
         Ok(header) => println!("fuzzer found as valid header of size {}", header.header_size())
     }
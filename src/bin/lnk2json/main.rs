@@ -1,7 +1,7 @@
 use clap::{Parser, ValueHint};
 use clio::Input;
-use encoding_rs::WINDOWS_1252;
-use lnk::ShellLink;
+use encoding_rs::{Encoding, WINDOWS_1252};
+use lnk::{encoding_for_codepage, CodepageSelection, ParseOptions, ShellLink};
 use simplelog::{ColorChoice, Config, TermLogger, TerminalMode};
 
 #[derive(Parser)]
@@ -14,10 +14,32 @@ struct Cli {
     #[clap(short('P'), long("pretty"))]
     pub(crate) pretty: bool,
 
+    /// include the offset and size of every structure in the output
+    #[clap(short('L'), long("locations"))]
+    pub(crate) locations: bool,
+
+    /// code page of strings which are not stored as Unicode, either as
+    /// number (e.g. 1251) or as label (e.g. shift_jis). This overrides the
+    /// code page which is specified in the file.
+    #[clap(short('c'), long("codepage"), value_parser=parse_codepage)]
+    pub(crate) codepage: Option<&'static Encoding>,
+
+    /// guess the code page, if the file does not specify one
+    #[clap(short('G'), long("guess-codepage"), conflicts_with = "codepage")]
+    pub(crate) guess_codepage: bool,
+
     #[clap(flatten)]
     pub(crate) verbose: clap_verbosity_flag::Verbosity,
 }
 
+fn parse_codepage(value: &str) -> Result<&'static Encoding, String> {
+    let encoding = match value.parse::<u32>() {
+        Ok(code_page) => encoding_for_codepage(code_page),
+        Err(_) => Encoding::for_label(value.as_bytes()),
+    };
+    encoding.ok_or_else(|| format!("unsupported code page: {value}"))
+}
+
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
     let _ = TermLogger::init(
@@ -34,12 +56,31 @@ fn main() -> anyhow::Result<()> {
         anyhow::bail!("you did not specify a file");
     }
 
-    let shell_link = ShellLink::open(cli.input_file.path().path(), WINDOWS_1252)?;
+    let codepage_selection = if cli.codepage.is_some() {
+        CodepageSelection::Fixed
+    } else if cli.guess_codepage {
+        CodepageSelection::Guess
+    } else {
+        CodepageSelection::FromLink
+    };
+    let shell_link = ShellLink::open_with_options(
+        cli.input_file.path().path(),
+        cli.codepage.unwrap_or(WINDOWS_1252),
+        ParseOptions {
+            codepage_selection,
+            ..Default::default()
+        },
+    )?;
+
+    let mut json = serde_json::to_value(&shell_link)?;
+    if cli.locations {
+        json["locations"] = serde_json::to_value(shell_link.locations())?;
+    }
 
     if cli.pretty {
-        println!("{}", serde_json::to_string_pretty(&shell_link)?);
+        println!("{}", serde_json::to_string_pretty(&json)?);
     } else {
-        println!("{}", serde_json::to_string(&shell_link)?);
+        println!("{}", serde_json::to_string(&json)?);
     }
     Ok(())
 }
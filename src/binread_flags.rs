@@ -1,14 +1,17 @@
 macro_rules! binread_flags {
     ($type: ty, $repr:ty) => {
         impl binrw::BinRead for $type {
-            type Args<'a> = ();
+            /// unknown bits are rejected, unless a lenient
+            /// [`ParseContext`](crate::parse_options::ParseContext) is passed
+            type Args<'a> = Option<&'a crate::parse_options::ParseContext>;
 
             fn read_options<R: std::io::Read + std::io::Seek>(
                 reader: &mut R,
                 endian: binrw::Endian,
-                _args: Self::Args<'_>,
+                args: Self::Args<'_>,
             ) -> binrw::BinResult<Self> {
                 use binrw::BinReaderExt;
+                let pos = reader.stream_position()?;
                 let raw: $repr = match endian {
                     binrw::Endian::Big => reader.read_be()?,
                     binrw::Endian::Little => reader.read_le()?,
@@ -16,10 +19,23 @@ macro_rules! binread_flags {
 
                 match Self::from_bits(raw) {
                     Some(res) => Ok(res),
-                    None => Err(binrw::Error::AssertFail {
-                        pos: reader.stream_position()?,
-                        message: format!("unable to convert '0x{raw:x}' to {}", stringify!($type)),
-                    }),
+                    None => {
+                        let error = crate::error::FieldError::new(
+                            stringify!($type),
+                            "a combination of known flags",
+                            format!("0x{raw:x}"),
+                        );
+                        match args {
+                            Some(ctx) => {
+                                ctx.deviation(pos, error)?;
+                                Ok(Self::from_bits_retain(raw))
+                            }
+                            None => Err(binrw::Error::Custom {
+                                pos,
+                                err: Box::new(error),
+                            }),
+                        }
+                    }
                 }
             }
         }
@@ -0,0 +1,573 @@
+use encoding_rs::{Encoding, WINDOWS_1252};
+use getset::{Getters, Setters};
+
+use crate::extradata::console_data::ConsoleDataBlock;
+use crate::extradata::environment_variable_data::EnvironmentVariableDataBlock;
+use crate::extradata::ExtraDataBlock;
+use crate::generic_types::idlist::{split_unc_path, windows_drive_letter};
+use crate::linkinfo::{
+    CommonNetworkRelativeLink, DriveType, LinkInfo, NetworkProviderType, VolumeID,
+};
+use crate::shell_item::file_entry::FileEntryMetadata;
+use crate::{
+    BuildError, DosDateTime, FileAttributeFlags, FileTime, HotkeyFlags, IdList, LinkFlags,
+    LinkTargetIdList, ShellLink, ShellLinkHeader, ShowCommand,
+};
+
+/// the maximum number of characters of a string in the StringData section
+const MAX_STRING_DATA_LENGTH: usize = u16::MAX as usize;
+
+/// the maximum number of characters of the target of an
+/// EnvironmentVariableDataBlock, excluding the terminating NULL character.
+/// For the ANSI target, this is the maximum number of bytes.
+const MAX_ENVIRONMENT_TARGET_LENGTH: usize = 259;
+
+/// The metadata of a link target, which are stored in the
+/// [`ShellLinkHeader`] and in the [`LinkTargetIdList`] of a new shell link.
+/// They are used by Windows if the target is not available.
+#[derive(Clone, Debug, Getters, Setters)]
+#[getset(get = "pub", set = "pub")]
+pub struct TargetMetadata {
+    /// whether the target is a directory
+    is_directory: bool,
+
+    /// the size of the target, in bytes
+    file_size: u64,
+
+    /// the attributes of the target
+    file_attributes: FileAttributeFlags,
+
+    /// the creation time of the target
+    #[getset(skip)]
+    creation_time: FileTime,
+
+    /// the last access time of the target
+    #[getset(skip)]
+    access_time: FileTime,
+
+    /// the last modification time of the target
+    #[getset(skip)]
+    write_time: FileTime,
+}
+
+impl TargetMetadata {
+    /// Create the metadata of a file, without size and timestamps
+    pub fn file() -> Self {
+        Self::new(false, FileAttributeFlags::FILE_ATTRIBUTE_ARCHIVE)
+    }
+
+    /// Create the metadata of a directory, without timestamps
+    pub fn directory() -> Self {
+        Self::new(true, FileAttributeFlags::FILE_ATTRIBUTE_DIRECTORY)
+    }
+
+    fn new(is_directory: bool, file_attributes: FileAttributeFlags) -> Self {
+        Self {
+            is_directory,
+            file_size: 0,
+            file_attributes,
+            creation_time: FileTime::default(),
+            access_time: FileTime::default(),
+            write_time: FileTime::default(),
+        }
+    }
+
+    /// returns the creation time of the target
+    pub fn creation_time(&self) -> &FileTime {
+        &self.creation_time
+    }
+
+    /// sets the creation time of the target. Anything which can be
+    /// converted to a [`FileTime`] is accepted, like a [`std::time::SystemTime`].
+    pub fn set_creation_time(&mut self, creation_time: impl Into<FileTime>) -> &mut Self {
+        self.creation_time = creation_time.into();
+        self
+    }
+
+    /// returns the last access time of the target
+    pub fn access_time(&self) -> &FileTime {
+        &self.access_time
+    }
+
+    /// sets the last access time of the target, see
+    /// [`TargetMetadata::set_creation_time`]
+    pub fn set_access_time(&mut self, access_time: impl Into<FileTime>) -> &mut Self {
+        self.access_time = access_time.into();
+        self
+    }
+
+    /// returns the last modification time of the target
+    pub fn write_time(&self) -> &FileTime {
+        &self.write_time
+    }
+
+    /// sets the last modification time of the target, see
+    /// [`TargetMetadata::set_creation_time`]
+    pub fn set_write_time(&mut self, write_time: impl Into<FileTime>) -> &mut Self {
+        self.write_time = write_time.into();
+        self
+    }
+
+    /// stores the metadata in `header`. Like Windows does, only the lower 32
+    /// bits of the file size are stored.
+    fn apply_to(&self, header: &mut ShellLinkHeader) {
+        header.set_file_attributes(self.file_attributes);
+        header.set_file_size(self.file_size as u32);
+        header.set_creation_time(self.creation_time.clone());
+        header.set_access_time(self.access_time.clone());
+        header.set_write_time(self.write_time.clone());
+    }
+
+    /// returns the metadata which are stored in the file entry shell item
+    /// of the target
+    fn file_entry_metadata(&self) -> FileEntryMetadata {
+        let dos_date_time =
+            |time: &FileTime| DosDateTime::from_datetime(time.datetime()).unwrap_or_default();
+        FileEntryMetadata {
+            is_directory: self.is_directory,
+            file_size: self.file_size as u32,
+            file_attributes: self.file_attributes,
+            modification_time: dos_date_time(&self.write_time),
+            creation_time: dos_date_time(&self.creation_time),
+            access_time: dos_date_time(&self.access_time),
+        }
+    }
+}
+
+impl Default for TargetMetadata {
+    fn default() -> Self {
+        Self::file()
+    }
+}
+
+/// Creates a [`ShellLink`] from scratch, keeping the [`LinkFlags`]
+/// consistent with the structures which are present.
+///
+/// All invariants of the shell link are checked by
+/// [`ShellLinkBuilder::build`], so that an invalid link is never created.
+///
+/// ```
+/// use lnk::{ShellLinkBuilder, ShowCommand};
+///
+/// let link = ShellLinkBuilder::new()
+///     .target(r"C:\Windows\System32\notepad.exe")
+///     .arguments("readme.txt")
+///     .working_dir(r"C:\Users\Public")
+///     .show_command(ShowCommand::ShowMaximized)
+///     .build()
+///     .unwrap();
+/// assert_eq!(
+///     link.link_target().as_deref(),
+///     Some(r"C:\Windows\System32\notepad.exe")
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct ShellLinkBuilder {
+    target: Option<String>,
+    target_metadata: Option<TargetMetadata>,
+    network_device: Option<String>,
+    network_provider_type: NetworkProviderType,
+    environment_target: Option<String>,
+    arguments: Option<String>,
+    working_dir: Option<String>,
+    relative_path: Option<String>,
+    description: Option<String>,
+    icon: Option<(String, i32)>,
+    hotkey: Option<HotkeyFlags>,
+    show_command: ShowCommand,
+    console: Option<ConsoleDataBlock>,
+    unicode: bool,
+    default_codepage: &'static Encoding,
+}
+
+impl Default for ShellLinkBuilder {
+    fn default() -> Self {
+        Self {
+            target: None,
+            target_metadata: None,
+            network_device: None,
+            network_provider_type: NetworkProviderType::Smb,
+            environment_target: None,
+            arguments: None,
+            working_dir: None,
+            relative_path: None,
+            description: None,
+            icon: None,
+            hotkey: None,
+            show_command: ShowCommand::ShowNormal,
+            console: None,
+            unicode: true,
+            default_codepage: WINDOWS_1252,
+        }
+    }
+}
+
+impl ShellLinkBuilder {
+    /// Create a new builder for a Unicode shell link without target
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the absolute path of the link target, like
+    /// `C:\Windows\notepad.exe`. It is stored in the [`LinkInfo`] and in
+    /// the [`LinkTargetIdList`], see [`IdList::from_windows_path`]. Paths of
+    /// directories end with a backslash.
+    ///
+    /// The target can also be a UNC path, like
+    /// `\\server\share\dir\file.exe`, which is only stored in the
+    /// [`LinkInfo`], using a [`CommonNetworkRelativeLink`].
+    ///
+    /// [`IdList::from_windows_path`]: crate::IdList::from_windows_path
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// Set the device which the network share of the link target is mapped
+    /// to, like the drive letter `Z:`. This is only used if the target is a
+    /// UNC path.
+    pub fn network_device(mut self, device_name: impl Into<String>) -> Self {
+        self.network_device = Some(device_name.into());
+        self
+    }
+
+    /// Set the type of the network provider of the link target's share,
+    /// which is [`NetworkProviderType::Smb`] by default. This is only used
+    /// if the target is a UNC path.
+    pub fn network_provider_type(mut self, network_provider_type: NetworkProviderType) -> Self {
+        self.network_provider_type = network_provider_type;
+        self
+    }
+
+    /// Set the metadata of the link target, like its size and timestamps. By
+    /// default, the target is a file without size and timestamps, or a
+    /// directory if its path ends with a backslash.
+    pub fn target_metadata(mut self, target_metadata: TargetMetadata) -> Self {
+        self.target_metadata = Some(target_metadata);
+        self
+    }
+
+    /// Set the path of the link target using environment variables, like
+    /// `%windir%\notepad.exe`. It is stored in an
+    /// [`EnvironmentVariableDataBlock`].
+    pub fn environment_target(mut self, target: impl Into<String>) -> Self {
+        self.environment_target = Some(target.into());
+        self
+    }
+
+    /// Set the command line arguments of the link target
+    pub fn arguments(mut self, arguments: impl Into<String>) -> Self {
+        self.arguments = Some(arguments.into());
+        self
+    }
+
+    /// Set the working directory of the link target
+    pub fn working_dir(mut self, working_dir: impl Into<String>) -> Self {
+        self.working_dir = Some(working_dir.into());
+        self
+    }
+
+    /// Set the path of the link target relative to the shell link
+    pub fn relative_path(mut self, relative_path: impl Into<String>) -> Self {
+        self.relative_path = Some(relative_path.into());
+        self
+    }
+
+    /// Set the description of the shell link, which is displayed as tooltip
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set the file which contains the icon of the shell link, and the index
+    /// of the icon within this file
+    pub fn icon(mut self, location: impl Into<String>, index: i32) -> Self {
+        self.icon = Some((location.into(), index));
+        self
+    }
+
+    /// Set the keystrokes which activate the link target
+    pub fn hotkey(mut self, hotkey: HotkeyFlags) -> Self {
+        self.hotkey = Some(hotkey);
+        self
+    }
+
+    /// Set the window state of the launched application
+    pub fn show_command(mut self, show_command: ShowCommand) -> Self {
+        self.show_command = show_command;
+        self
+    }
+
+    /// Set the settings of the console window, if the link target is a
+    /// console application
+    pub fn console(mut self, console: ConsoleDataBlock) -> Self {
+        self.console = Some(console);
+        self
+    }
+
+    /// Set whether strings are stored as Unicode, which is the default.
+    /// Otherwise, all strings must be encodable using the
+    /// [`ShellLinkBuilder::default_codepage`].
+    pub fn unicode(mut self, unicode: bool) -> Self {
+        self.unicode = unicode;
+        self
+    }
+
+    /// Set the code page which is used for strings which are not stored as
+    /// Unicode. The default is Windows-1252.
+    pub fn default_codepage(mut self, default_codepage: &'static Encoding) -> Self {
+        self.default_codepage = default_codepage;
+        self
+    }
+
+    /// Create the shell link, after checking that
+    ///
+    /// - a target path or an environment variable target has been set,
+    /// - the target path is an absolute Windows path,
+    /// - no string is longer than its structure permits, and
+    /// - all strings can be encoded if the link does not use Unicode.
+    pub fn build(self) -> Result<ShellLink, BuildError> {
+        if self.target.is_none() && self.environment_target.is_none() {
+            return Err(BuildError::MissingTarget);
+        }
+
+        let strings = [
+            ("Arguments", self.arguments.as_ref()),
+            ("WorkingDir", self.working_dir.as_ref()),
+            ("RelativePath", self.relative_path.as_ref()),
+            ("Description", self.description.as_ref()),
+            (
+                "IconLocation",
+                self.icon.as_ref().map(|(location, _)| location),
+            ),
+        ];
+        for (field, value) in strings {
+            if let Some(value) = value {
+                self.check_string(field, value, MAX_STRING_DATA_LENGTH)?;
+            }
+        }
+
+        let environment_props = self
+            .environment_target
+            .as_deref()
+            .map(|target| self.environment_variable_data(target))
+            .transpose()?;
+
+        let mut link = ShellLink::default();
+        link.set_default_codepage(self.default_codepage);
+        link.header_mut()
+            .update_link_flags(LinkFlags::IS_UNICODE, self.unicode);
+
+        if let Some(target) = &self.target {
+            let metadata = match &self.target_metadata {
+                Some(metadata) => metadata.clone(),
+                None if target.ends_with('\\') => TargetMetadata::directory(),
+                None => TargetMetadata::file(),
+            };
+
+            if target.starts_with("\\\\") {
+                link.set_link_info(Some(self.network_link_info(target)?));
+            } else {
+                // Windows stores the paths of directories without trailing
+                // backslash, except for root directories
+                let local_base_path = target
+                    .strip_suffix('\\')
+                    .filter(|path| path.len() > 2)
+                    .unwrap_or(target);
+                link.set_link_info(Some(self.local_link_info(local_base_path)?));
+
+                let id_list =
+                    IdList::from_windows_path_with(target, &metadata.file_entry_metadata())?;
+                let id_list = LinkTargetIdList::new(id_list)
+                    .ok_or_else(|| BuildError::InvalidTargetPath(target.clone()))?;
+                link.set_linktarget_id_list(Some(id_list));
+            }
+            metadata.apply_to(link.header_mut());
+        }
+
+        if let Some(environment_props) = environment_props {
+            link.insert_extra_data_block(ExtraDataBlock::EnvironmentProps(environment_props));
+        }
+
+        if let Some(console) = self.console {
+            link.insert_extra_data_block(ExtraDataBlock::ConsoleProps(console));
+        }
+
+        link.set_arguments(self.arguments);
+        link.set_working_dir(self.working_dir);
+        link.set_relative_path(self.relative_path);
+        link.set_name(self.description);
+        if let Some((location, index)) = self.icon {
+            link.set_icon_location(Some(location));
+            link.header_mut().set_icon_index(index);
+        }
+        if let Some(hotkey) = self.hotkey {
+            link.header_mut().set_hotkey(hotkey);
+        }
+        link.header_mut().set_show_command(self.show_command);
+
+        Ok(link)
+    }
+
+    /// creates the [`LinkInfo`] of a target on a local volume. If the path
+    /// cannot be encoded using the default code page, it is stored as
+    /// Unicode string as well.
+    fn local_link_info(&self, target: &str) -> Result<LinkInfo, BuildError> {
+        if windows_drive_letter(target).is_none() {
+            return Err(BuildError::InvalidTargetPath(target.to_string()));
+        }
+        self.check_string("Target", target, MAX_STRING_DATA_LENGTH)?;
+
+        let (local_base_path, local_base_path_unicode) = self.ansi_and_unicode(target);
+        let volume_id = VolumeID::new(DriveType::DriveFixed, 0, "");
+        Ok(LinkInfo::new_local(
+            volume_id,
+            local_base_path,
+            local_base_path_unicode,
+        ))
+    }
+
+    /// creates the [`LinkInfo`] of a target on a network share, which is
+    /// identified by the UNC path `target`
+    fn network_link_info(&self, target: &str) -> Result<LinkInfo, BuildError> {
+        let (net_name, common_path_suffix) = split_unc_path(target)
+            .ok_or_else(|| BuildError::InvalidTargetPath(target.to_string()))?;
+        self.check_string("Target", target, MAX_STRING_DATA_LENGTH)?;
+
+        let (net_name, net_name_unicode) = self.ansi_and_unicode(net_name);
+        let mut link =
+            CommonNetworkRelativeLink::new(net_name, Some(self.network_provider_type.clone()));
+        link.set_net_name_unicode(net_name_unicode);
+        if let Some(device_name) = &self.network_device {
+            self.check_string("NetworkDevice", device_name, MAX_STRING_DATA_LENGTH)?;
+            let (device_name, device_name_unicode) = self.ansi_and_unicode(device_name);
+            link.set_device_name(Some(device_name));
+            link.set_device_name_unicode(device_name_unicode);
+        }
+
+        let (common_path_suffix, common_path_suffix_unicode) =
+            self.ansi_and_unicode(common_path_suffix);
+        Ok(LinkInfo::new_network(
+            link,
+            common_path_suffix,
+            common_path_suffix_unicode,
+        ))
+    }
+
+    /// creates the [`EnvironmentVariableDataBlock`] of `target`. The ANSI
+    /// target is always written, so it must fit into its buffer even if the
+    /// link uses Unicode. Characters which cannot be encoded using the
+    /// default code page are replaced by `?` in the ANSI target.
+    fn environment_variable_data(
+        &self,
+        target: &str,
+    ) -> Result<EnvironmentVariableDataBlock, BuildError> {
+        const FIELD: &str = "EnvironmentTarget";
+        self.check_string(FIELD, target, MAX_ENVIRONMENT_TARGET_LENGTH)?;
+
+        let (target_ansi, _) = self.ansi_and_unicode(target);
+        let (encoded, _, _) = self.default_codepage.encode(&target_ansi);
+        if encoded.len() > MAX_ENVIRONMENT_TARGET_LENGTH {
+            return Err(BuildError::StringTooLong {
+                field: FIELD,
+                length: encoded.len(),
+                max: MAX_ENVIRONMENT_TARGET_LENGTH,
+            });
+        }
+        Ok(EnvironmentVariableDataBlock::with_ansi_target(
+            target_ansi,
+            target,
+        ))
+    }
+
+    /// returns `value` as ANSI string, where all characters which cannot be
+    /// encoded using the default code page are replaced by `?`, and `value`
+    /// as Unicode string if such a replacement has been necessary
+    fn ansi_and_unicode(&self, value: &str) -> (String, Option<String>) {
+        let (_, _, had_errors) = self.default_codepage.encode(value);
+        if !had_errors {
+            return (value.to_string(), None);
+        }
+
+        let ansi = value
+            .chars()
+            .map(|c| {
+                let mut buffer = [0; 4];
+                let (_, _, had_errors) = self.default_codepage.encode(c.encode_utf8(&mut buffer));
+                if had_errors {
+                    '?'
+                } else {
+                    c
+                }
+            })
+            .collect();
+        (ansi, Some(value.to_string()))
+    }
+
+    /// checks that `value` has at most `max` characters, and that it can be
+    /// encoded using the default code page if the link does not use Unicode
+    fn check_string(&self, field: &'static str, value: &str, max: usize) -> Result<(), BuildError> {
+        let length = if self.unicode {
+            value.encode_utf16().count()
+        } else {
+            let (encoded, _, had_errors) = self.default_codepage.encode(value);
+            if had_errors {
+                return Err(BuildError::NotEncodable {
+                    field,
+                    codepage: self.default_codepage.name(),
+                });
+            }
+            encoded.len()
+        };
+        if length > max {
+            return Err(BuildError::StringTooLong { field, length, max });
+        }
+        Ok(())
+    }
+}
+
+impl ShellLink {
+    /// Create a [`ShellLinkBuilder`], which creates a shell link from scratch
+    pub fn builder() -> ShellLinkBuilder {
+        ShellLinkBuilder::new()
+    }
+
+    /// Create a new ShellLink pointing to the absolute Windows path `path`,
+    /// like `C:\Program Files\App\app.exe` or `\\server\share\app.exe`,
+    /// with otherwise default settings. Unlike [`ShellLink::new_simple`], the local file system is
+    /// not accessed, so the target does not need to exist. If `metadata` is
+    /// `None`, the target is a file, or a directory if `path` ends with a
+    /// backslash.
+    ///
+    /// The link contains a [`LinkInfo`] with the VolumeID of a fixed drive,
+    /// a [`LinkTargetIdList`] and, for files, the working directory. For UNC
+    /// paths, the LinkInfo contains a [`CommonNetworkRelativeLink`] instead,
+    /// and there is no LinkTargetIdList.
+    pub fn new_for_windows_path(
+        path: &str,
+        metadata: Option<TargetMetadata>,
+    ) -> Result<Self, BuildError> {
+        let is_directory = match &metadata {
+            Some(metadata) => metadata.is_directory,
+            None => path.ends_with('\\'),
+        };
+
+        let mut builder = Self::builder().target(path);
+        if let Some(metadata) = metadata {
+            builder = builder.target_metadata(metadata);
+        }
+        if !is_directory {
+            if let Some((parent, _)) = path.rsplit_once('\\') {
+                // the parent of a file in a root directory is the root
+                // directory itself
+                let working_dir = if parent.len() == 2 {
+                    format!("{parent}\\")
+                } else {
+                    parent.to_string()
+                };
+                builder = builder.working_dir(working_dir);
+            }
+        }
+        builder.build()
+    }
+}
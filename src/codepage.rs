@@ -0,0 +1,145 @@
+use encoding_rs::{
+    Encoding, BIG5, EUC_KR, GBK, IBM866, KOI8_R, KOI8_U, SHIFT_JIS, UTF_8, WINDOWS_1250,
+    WINDOWS_1251, WINDOWS_1252, WINDOWS_1253, WINDOWS_1254, WINDOWS_1255, WINDOWS_1256,
+    WINDOWS_1257, WINDOWS_1258, WINDOWS_874,
+};
+#[allow(unused)]
+use log::{debug, error, info, trace, warn};
+
+use crate::{extradata::ExtraDataBlock, ShellLink};
+
+/// The ANSI code pages which are tried when guessing the code page of a
+/// shell link, see [`CodepageSelection::Guess`](crate::CodepageSelection::Guess)
+const GUESS_CANDIDATES: [&Encoding; 13] = [
+    WINDOWS_1252,
+    WINDOWS_1250,
+    WINDOWS_1251,
+    WINDOWS_1253,
+    WINDOWS_1254,
+    WINDOWS_1255,
+    WINDOWS_1256,
+    WINDOWS_1257,
+    WINDOWS_874,
+    SHIFT_JIS,
+    GBK,
+    EUC_KR,
+    BIG5,
+];
+
+/// returns the encoding of the Windows code page `code_page`, e.g. 1252 or
+/// 932. Returns `None` if the code page is unknown or not supported by
+/// `encoding_rs`.
+pub fn encoding_for_codepage(code_page: u32) -> Option<&'static Encoding> {
+    let encoding = match code_page {
+        866 => IBM866,
+        874 => WINDOWS_874,
+        932 => SHIFT_JIS,
+        936 => GBK,
+        949 => EUC_KR,
+        950 => BIG5,
+        1250 => WINDOWS_1250,
+        1251 => WINDOWS_1251,
+        1252 => WINDOWS_1252,
+        1253 => WINDOWS_1253,
+        1254 => WINDOWS_1254,
+        1255 => WINDOWS_1255,
+        1256 => WINDOWS_1256,
+        1257 => WINDOWS_1257,
+        1258 => WINDOWS_1258,
+        20866 => KOI8_R,
+        21866 => KOI8_U,
+        65001 => UTF_8,
+        _ => return None,
+    };
+    Some(encoding)
+}
+
+/// returns the ANSI code page of a system whose console uses the code page
+/// `code_page`. Consoles usually use an OEM code page, e.g. 866 on Russian
+/// systems, while ANSI strings are stored using the ANSI code page, e.g. 1251.
+fn ansi_codepage_for_console(code_page: u32) -> u32 {
+    match code_page {
+        437 | 850 | 858 | 860 | 861 | 863 | 865 => 1252,
+        737 | 869 => 1253,
+        775 => 1257,
+        852 => 1250,
+        855 | 866 => 1251,
+        857 => 1254,
+        862 => 1255,
+        720 | 864 => 1256,
+        code_page => code_page,
+    }
+}
+
+/// returns the encoding which is specified by the ConsoleFEDataBlock of
+/// `link`, if there is one
+pub(crate) fn codepage_from_link(link: &ShellLink) -> Option<&'static Encoding> {
+    let block = link.extra_data().console_fe()?;
+    let code_page = ansi_codepage_for_console(*block.code_page());
+    let encoding = encoding_for_codepage(code_page);
+    if encoding.is_none() {
+        debug!("ignoring unsupported code page {code_page} of ConsoleFEDataBlock");
+    }
+    encoding
+}
+
+/// guesses the code page of `link` by comparing its ANSI strings, which have
+/// been decoded using [`ShellLink::default_codepage`], with their Unicode
+/// counterparts. Returns `None` if there is no evidence for any code page.
+pub(crate) fn guess_codepage(link: &ShellLink) -> Option<&'static Encoding> {
+    let used = link.default_codepage();
+
+    // restore the raw bytes of the ANSI strings, as far as possible
+    let pairs: Vec<(Vec<u8>, &str)> = string_pairs(link)
+        .into_iter()
+        .filter(|(ansi, unicode)| !ansi.is_ascii() || !unicode.is_ascii())
+        // characters which are not part of the code page of the system
+        // which created the link have been replaced by '?'
+        .filter(|(ansi, unicode)| ansi.matches('?').count() == unicode.matches('?').count())
+        .filter_map(|(ansi, unicode)| {
+            let (raw, _, had_errors) = used.encode(ansi);
+            (!had_errors).then(|| (raw.into_owned(), unicode))
+        })
+        .collect();
+    if pairs.is_empty() {
+        return None;
+    }
+
+    std::iter::once(used)
+        .chain(GUESS_CANDIDATES)
+        .find(|candidate| {
+            pairs.iter().all(|(raw, unicode)| {
+                let (decoded, had_errors) = candidate.decode_without_bom_handling(raw);
+                !had_errors && decoded == *unicode
+            })
+        })
+}
+
+/// returns all ANSI strings of `link` which have a Unicode counterpart
+fn string_pairs(link: &ShellLink) -> Vec<(&str, &str)> {
+    let mut pairs = Vec::new();
+
+    if let Some(info) = link.link_info() {
+        pairs.extend(info.string_pairs());
+    }
+
+    for block in link.extra_data() {
+        let (ansi, unicode) = match block {
+            ExtraDataBlock::EnvironmentProps(block) => {
+                (block.target_ansi(), block.target_unicode())
+            }
+            ExtraDataBlock::IconEnvironmentProps(block) => {
+                (block.target_ansi(), block.target_unicode())
+            }
+            ExtraDataBlock::DarwinProps(block) => {
+                (block.darwin_data_ansi(), block.darwin_data_unicode())
+            }
+            _ => continue,
+        };
+        if let Some(unicode) = unicode {
+            pairs.push((ansi, unicode));
+        }
+    }
+
+    pairs
+}
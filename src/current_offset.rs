@@ -1,10 +1,13 @@
 use binrw::BinRead;
 use log::trace;
 
+use crate::parse_options::ParseContext;
+
 /// implements [`BinRead`] by reading the current cursor position
-/// and storing it as `u32`
-#[derive(Clone, Debug)]
-pub struct CurrentOffset(u32);
+/// and storing it as `u64`, so that structures can be read from streams
+/// larger than 4 GiB
+#[derive(Clone, Debug, Default)]
+pub struct CurrentOffset(u64);
 
 impl BinRead for CurrentOffset {
     type Args<'a> = ();
@@ -16,12 +19,52 @@ impl BinRead for CurrentOffset {
     ) -> binrw::BinResult<Self> {
         let pos = reader.stream_position()?;
         trace!("read offset at 0x{pos:016x}");
-        Ok(Self(pos.try_into().expect("invalid offset")))
+        Ok(Self(pos))
+    }
+}
+
+impl CurrentOffset {
+    /// returns the absolute position of `relative`, which is relative to
+    /// this offset
+    pub(crate) fn at(&self, relative: u64) -> u64 {
+        self.0 + relative
     }
 }
 
-impl AsRef<u32> for CurrentOffset {
-    fn as_ref(&self) -> &u32 {
+impl AsRef<u64> for CurrentOffset {
+    fn as_ref(&self) -> &u64 {
         &self.0
     }
 }
+
+/// implements [`BinRead`] by reading a `T` and remembering where it has
+/// been found, so that its location can be recorded in the [`ParseContext`],
+/// see [`crate::ShellLink::locations`]
+pub(crate) struct Located<T> {
+    value: T,
+    start: u64,
+    end: u64,
+}
+
+impl<T: BinRead> BinRead for Located<T> {
+    type Args<'a> = T::Args<'a>;
+
+    fn read_options<R: std::io::Read + std::io::Seek>(
+        reader: &mut R,
+        endian: binrw::Endian,
+        args: Self::Args<'_>,
+    ) -> binrw::BinResult<Self> {
+        let start = reader.stream_position()?;
+        let value = T::read_options(reader, endian, args)?;
+        let end = reader.stream_position()?;
+        Ok(Self { value, start, end })
+    }
+}
+
+impl<T> Located<T> {
+    /// records the location of the value as `path` and returns the value
+    pub(crate) fn record(self, ctx: &ParseContext, path: &str) -> T {
+        ctx.record_location(path, self.start, self.end);
+        self.value
+    }
+}
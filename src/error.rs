@@ -0,0 +1,273 @@
+use std::fmt;
+
+use getset::Getters;
+#[cfg(feature = "serde")]
+use serde::Serialize;
+use thiserror::Error;
+
+/// The error type for shell link parsing errors.
+#[derive(Debug, Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("An IO error occurred: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("The parsed file isn't a shell link.")]
+    NotAShellLinkError,
+
+    #[error("Error while parsing: {0}")]
+    BinReadError(#[from] binrw::Error),
+
+    #[error("The link flags require a {0} structure, but it is missing.")]
+    MissingStructure(&'static str),
+
+    #[error("Error while parsing {0}")]
+    ParseError(#[from] ParseError),
+}
+
+/// The error type for shell links which cannot be built by
+/// [`ShellLinkBuilder`](crate::ShellLinkBuilder)
+#[derive(Clone, Debug, Eq, PartialEq, Error)]
+pub enum BuildError {
+    /// Neither a target path nor an environment variable target has been set
+    #[error("a shell link needs a target path or an environment variable target")]
+    MissingTarget,
+
+    /// The target path is not an absolute Windows path
+    #[error("{0:?} is not an absolute Windows path, like C:\\Windows")]
+    InvalidTargetPath(String),
+
+    /// A string is longer than its structure permits
+    #[error("{field} has {length} characters, but at most {max} characters are allowed")]
+    StringTooLong {
+        /// the name of the string
+        field: &'static str,
+        /// the length of the string
+        length: usize,
+        /// the maximum length of the string
+        max: usize,
+    },
+
+    /// A string cannot be stored as ANSI string
+    #[error("{field} cannot be encoded using the code page {codepage}")]
+    NotEncodable {
+        /// the name of the string
+        field: &'static str,
+        /// the name of the code page
+        codepage: &'static str,
+    },
+}
+
+/// A section of a shell link
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum Section {
+    /// The ShellLinkHeader
+    Header,
+
+    /// The LinkTargetIDList
+    LinkTargetIdList,
+
+    /// The LinkInfo structure
+    LinkInfo,
+
+    /// The StringData section
+    StringData,
+
+    /// The ExtraData section, outside of any block
+    ExtraData,
+
+    /// The ExtraData block with the given signature
+    ExtraDataBlock(u32),
+}
+
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Header => write!(f, "ShellLinkHeader"),
+            Self::LinkTargetIdList => write!(f, "LinkTargetIDList"),
+            Self::LinkInfo => write!(f, "LinkInfo"),
+            Self::StringData => write!(f, "StringData"),
+            Self::ExtraData => write!(f, "ExtraData"),
+            Self::ExtraDataBlock(signature) => write!(f, "ExtraData block 0x{signature:08x}"),
+        }
+    }
+}
+
+/// Describes where and why a shell link could not be parsed
+#[derive(Clone, Debug, Eq, PartialEq, Getters)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[getset(get = "pub")]
+pub struct ParseError {
+    /// The section which could not be parsed
+    section: Section,
+
+    /// The absolute offset, in bytes, where the error has been found
+    offset: u64,
+
+    /// The name of the field which could not be parsed, if known
+    field: Option<String>,
+
+    /// The value which has been expected, if known
+    expected: Option<String>,
+
+    /// The value which has been found, if known
+    actual: Option<String>,
+
+    /// A description of the error
+    message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset 0x{:08x}", self.section, self.offset)?;
+        if let Some(field) = &self.field {
+            write!(f, " (field {field})")?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl ParseError {
+    /// converts an error which occurred while reading `section`. If the
+    /// error does not contain a position, `fallback_offset` is used.
+    pub(crate) fn from_binrw(section: Section, error: &binrw::Error, fallback_offset: u64) -> Self {
+        let mut result = Self {
+            section,
+            offset: fallback_offset,
+            field: None,
+            expected: None,
+            actual: None,
+            message: String::new(),
+        };
+        result.update(error);
+        result
+    }
+
+    fn update(&mut self, error: &binrw::Error) {
+        match error {
+            binrw::Error::Backtrace(backtrace) => {
+                // the first frame is the innermost one
+                if let Some(field) = backtrace.frames.iter().find_map(field_of_frame) {
+                    self.field = Some(field);
+                }
+                self.update(&backtrace.error);
+            }
+            binrw::Error::Custom { pos, err } => {
+                self.offset = *pos;
+                if let Some(err) = err.downcast_ref::<FieldError>() {
+                    self.field = Some(err.field.to_string());
+                    self.expected = Some(err.expected.clone());
+                    self.actual = Some(err.actual.clone());
+                    self.message = err.to_string();
+                } else if let Some(err) = err.downcast_ref::<ExtraDataBlockError>() {
+                    self.section = Section::ExtraDataBlock(err.signature);
+                    self.update(&err.error);
+                } else {
+                    self.message = err.to_string();
+                }
+            }
+            binrw::Error::EnumErrors {
+                pos,
+                variant_errors,
+            } => {
+                // variants which do not match are not interesting
+                match variant_errors
+                    .iter()
+                    .map(|(_, error)| error)
+                    .find(|error| !matches!(error.root_cause(), binrw::Error::BadMagic { .. }))
+                {
+                    Some(error) => self.update(error),
+                    None => {
+                        self.offset = *pos;
+                        self.message = "no variant matched".to_string();
+                    }
+                }
+            }
+            binrw::Error::AssertFail { pos, message } => {
+                self.offset = *pos;
+                self.message = message.clone();
+            }
+            binrw::Error::BadMagic { pos, found } => {
+                self.offset = *pos;
+                self.actual = Some(format!("{found:?}"));
+                self.message = format!("bad magic value {found:?}");
+            }
+            binrw::Error::NoVariantMatch { pos } => {
+                self.offset = *pos;
+                self.message = "no variant matched".to_string();
+            }
+            error => self.message = error.to_string(),
+        }
+    }
+}
+
+/// extracts the field name from a backtrace frame which has been generated
+/// by binrw
+fn field_of_frame(frame: &binrw::error::BacktraceFrame) -> Option<String> {
+    match frame {
+        binrw::error::BacktraceFrame::Full { message, .. }
+        | binrw::error::BacktraceFrame::Message(message) => {
+            let rest = message.strip_prefix("While parsing field '")?;
+            let (field, _) = rest.split_once('\'')?;
+            // fields of tuple variants have generated names
+            if field.starts_with("self_") {
+                None
+            } else {
+                Some(field.to_string())
+            }
+        }
+        binrw::error::BacktraceFrame::Custom(_) => None,
+    }
+}
+
+/// A field whose value violates the specification
+#[derive(Debug)]
+pub(crate) struct FieldError {
+    field: &'static str,
+    expected: String,
+    actual: String,
+}
+
+impl FieldError {
+    pub(crate) fn new(
+        field: &'static str,
+        expected: impl Into<String>,
+        actual: impl Into<String>,
+    ) -> Self {
+        Self {
+            field,
+            expected: expected.into(),
+            actual: actual.into(),
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid {}: expected {}, found {}",
+            self.field, self.expected, self.actual
+        )
+    }
+}
+
+/// An error in an ExtraData block, which remembers the signature of the block
+#[derive(Debug)]
+pub(crate) struct ExtraDataBlockError {
+    pub(crate) signature: u32,
+    pub(crate) error: binrw::Error,
+}
+
+impl fmt::Display for ExtraDataBlockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "in ExtraData block 0x{:08x}: {}",
+            self.signature, self.error
+        )
+    }
+}
@@ -1,3 +1,7 @@
+use std::io::{Read, SeekFrom};
+
+#[cfg(feature = "binwrite")]
+use binrw::BinWrite;
 use binrw::{BinRead, BinReaderExt};
 use encoding_rs::Encoding;
 #[allow(unused)]
@@ -6,6 +10,11 @@ use log::{debug, error, info, trace, warn};
 #[cfg(feature = "serde")]
 use serde::Serialize;
 
+use crate::error::{ExtraDataBlockError, ParseError, Section};
+#[cfg(feature = "binwrite")]
+use crate::raw_bytes::{write_preserving, RawBytes};
+use crate::{parse_options::ParseContext, LinkFlags};
+
 use self::{
     console_data::ConsoleDataBlock, console_fe_data::ConsoleFEDataBlock,
     darwin_data::DarwinDataBlock, environment_variable_data::EnvironmentVariableDataBlock,
@@ -73,22 +82,18 @@ pub mod tracker_data;
 /// (section 2.2) on platforms that support it.
 pub mod vista_and_above_id_list_data;
 
-/// ExtraData refers to a set of structures that convey additional information
-/// about a link target. These optional structures can be present in an extra
-/// data section that is appended to the basic Shell Link Binary File Format.
-///
-/// At the moment, ExtraData can only be read, not written to shortcuts
-mod shell_item_identifiers;
+/// The ShellItemIdentifiers structure contains a list of item identifiers.
+pub mod shell_item_identifiers;
 
 #[allow(missing_docs)]
 #[derive(Clone, Debug, BinRead)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
-#[br(import(_block_size: u32, _default_codepage: &'static Encoding))]
+#[br(import(_block_size: u32, _default_codepage: &'static Encoding, _ctx: &ParseContext))]
 pub enum ExtraDataBlock {
     #[br(magic = 0xa0000001u32)]
     EnvironmentProps(#[br(args(_block_size, _default_codepage))] EnvironmentVariableDataBlock),
     #[br(magic = 0xa0000002u32)]
-    ConsoleProps(#[br(args(_block_size))] ConsoleDataBlock),
+    ConsoleProps(#[br(args(_block_size, _ctx))] ConsoleDataBlock),
     #[br(magic = 0xa0000003u32)]
     TrackerProps(#[br(args(_block_size, _default_codepage))] TrackerDataBlock),
     #[br(magic = 0xa0000004u32)]
@@ -102,40 +107,406 @@ pub enum ExtraDataBlock {
     #[br(magic = 0xa0000008u32)]
     ShimProps(#[br(args(_block_size))] ShimDataBlock),
     #[br(magic = 0xa0000009u32)]
-    PropertyStoreProps(#[br(args(_block_size))] PropertyStoreDataBlock),
+    PropertyStoreProps(#[br(args(_block_size, _default_codepage))] PropertyStoreDataBlock),
     #[br(magic = 0xa000000au32)]
-    VistaAndAboveIdListProps(#[br(args(_block_size))] VistaAndAboveIdListDataBlock),
+    VistaAndAboveIdListProps(
+        #[br(args(_block_size, _default_codepage, _ctx))] VistaAndAboveIdListDataBlock,
+    ),
     #[br(magic = 0xa000000bu32)]
     KnownFolderProps(#[br(args(_block_size))] KnownFolderDataBlock),
     #[br(magic = 0xa000000cu32)]
-    ShellItemIdentifiers(#[br(args(_block_size))] ShellItemIdentifiers),
+    ShellItemIdentifiers(#[br(args(_block_size, _default_codepage, _ctx))] ShellItemIdentifiers),
+
+    /// A block with an unknown signature, e.g. a vendor specific block. Its
+    /// data are preserved as they are. In lenient mode, known blocks which
+    /// cannot be decoded are stored this way, too.
+    Unknown {
+        /// The signature of the block
+        #[br(assert(!(0xa0000001..=0xa000000c).contains(&signature)))]
+        signature: u32,
+
+        /// The data of the block, without BlockSize and BlockSignature
+        #[br(count = _block_size.saturating_sub(8))]
+        #[cfg_attr(feature = "serde", serde(serialize_with = "serialize_hex"))]
+        data: Vec<u8>,
+    },
+}
+
+/// serializes binary data as hex string
+#[cfg(feature = "serde")]
+fn serialize_hex<S>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    let hex: String = data.iter().map(|b| format!("{b:02x}")).collect();
+    serializer.serialize_str(&hex)
 }
 
+/// ExtraData refers to a set of structures that convey additional information
+/// about a link target. These optional structures can be present in an extra
+/// data section that is appended to the basic Shell Link Binary File Format.
 #[derive(Default, Debug)]
 #[allow(missing_docs, unused)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct ExtraData {
     blocks: Vec<ExtraDataBlock>,
+
+    /// the raw bytes of each block, see [`RawBytes`]
+    #[cfg(feature = "binwrite")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    raw_blocks: Vec<Option<RawBytes>>,
 }
 
-impl BinRead for ExtraData {
+impl ExtraDataBlock {
+    /// returns the [`LinkFlags`] bit which must be set if this block is
+    /// present, if any
+    pub fn link_flag(&self) -> Option<LinkFlags> {
+        match self {
+            Self::EnvironmentProps(_) => Some(LinkFlags::HAS_EXP_STRING),
+            Self::DarwinProps(_) => Some(LinkFlags::HAS_DARWIN_ID),
+            Self::IconEnvironmentProps(_) => Some(LinkFlags::HAS_EXP_ICON),
+            Self::ShimProps(_) => Some(LinkFlags::RUN_WITH_SHIM_LAYER),
+            _ => None,
+        }
+    }
+
+    /// returns the signature which identifies the type of this block
+    pub fn signature(&self) -> u32 {
+        match self {
+            Self::EnvironmentProps(_) => 0xa0000001,
+            Self::ConsoleProps(_) => 0xa0000002,
+            Self::TrackerProps(_) => 0xa0000003,
+            Self::ConsoleFeProps(_) => 0xa0000004,
+            Self::SpecialFolderProps(_) => 0xa0000005,
+            Self::DarwinProps(_) => 0xa0000006,
+            Self::IconEnvironmentProps(_) => 0xa0000007,
+            Self::ShimProps(_) => 0xa0000008,
+            Self::PropertyStoreProps(_) => 0xa0000009,
+            Self::VistaAndAboveIdListProps(_) => 0xa000000a,
+            Self::KnownFolderProps(_) => 0xa000000b,
+            Self::ShellItemIdentifiers(_) => 0xa000000c,
+            Self::Unknown { signature, .. } => *signature,
+        }
+    }
+}
+
+/// Writes the block, including the BlockSize and BlockSignature fields. The
+/// BlockSize is computed from the contents of the block.
+#[cfg(feature = "binwrite")]
+impl BinWrite for ExtraDataBlock {
     type Args<'a> = (&'static Encoding,);
 
+    fn write_options<W: std::io::Write + std::io::Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        args: Self::Args<'_>,
+    ) -> binrw::BinResult<()> {
+        let start = writer.stream_position()?;
+
+        // the block size is written after the block data are known
+        0u32.write_options(writer, endian, ())?;
+        self.signature().write_options(writer, endian, ())?;
+
+        match self {
+            Self::EnvironmentProps(block) => block.write_options(writer, endian, args)?,
+            Self::ConsoleProps(block) => block.write_options(writer, endian, ())?,
+            Self::TrackerProps(block) => block.write_options(writer, endian, args)?,
+            Self::ConsoleFeProps(block) => block.write_options(writer, endian, ())?,
+            Self::SpecialFolderProps(block) => block.write_options(writer, endian, ())?,
+            Self::DarwinProps(block) => block.write_options(writer, endian, args)?,
+            Self::IconEnvironmentProps(block) => block.write_options(writer, endian, args)?,
+            Self::ShimProps(block) => block.write_options(writer, endian, ())?,
+            Self::PropertyStoreProps(block) => block.write_options(writer, endian, ())?,
+            Self::VistaAndAboveIdListProps(block) => block.write_options(writer, endian, ())?,
+            Self::KnownFolderProps(block) => block.write_options(writer, endian, ())?,
+            Self::ShellItemIdentifiers(block) => block.write_options(writer, endian, ())?,
+            Self::Unknown { data, .. } => data.write_options(writer, endian, ())?,
+        }
+
+        let end = writer.stream_position()?;
+        let block_size = u32::try_from(end - start).map_err(|_| binrw::Error::AssertFail {
+            pos: start,
+            message: format!("ExtraData block of {} bytes is too large", end - start),
+        })?;
+        writer.seek(std::io::SeekFrom::Start(start))?;
+        block_size.write_options(writer, endian, ())?;
+        writer.seek(std::io::SeekFrom::Start(end))?;
+        Ok(())
+    }
+}
+
+/// generates a method which returns the first block of the given variant
+macro_rules! block_accessor {
+    ($(#[$meta:meta])* $name:ident, $variant:ident, $block_type:ty) => {
+        $(#[$meta])*
+        pub fn $name(&self) -> Option<&$block_type> {
+            self.blocks.iter().find_map(|block| match block {
+                ExtraDataBlock::$variant(block) => Some(block),
+                _ => None,
+            })
+        }
+    };
+}
+
+impl ExtraData {
+    /// returns all blocks, in the order in which they are stored
+    pub fn blocks(&self) -> &[ExtraDataBlock] {
+        &self.blocks
+    }
+
+    /// returns an iterator over all blocks
+    pub fn iter(&self) -> std::slice::Iter<'_, ExtraDataBlock> {
+        self.blocks.iter()
+    }
+
+    /// returns `true` if there are no blocks
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// returns the block which is identified by `signature`, if any
+    pub fn get(&self, signature: u32) -> Option<&ExtraDataBlock> {
+        self.blocks
+            .iter()
+            .find(|block| block.signature() == signature)
+    }
+
+    block_accessor!(
+        /// returns the EnvironmentVariableDataBlock, if any
+        environment_variables,
+        EnvironmentProps,
+        EnvironmentVariableDataBlock
+    );
+    block_accessor!(
+        /// returns the ConsoleDataBlock, if any
+        console,
+        ConsoleProps,
+        ConsoleDataBlock
+    );
+    block_accessor!(
+        /// returns the TrackerDataBlock, if any
+        tracker,
+        TrackerProps,
+        TrackerDataBlock
+    );
+    block_accessor!(
+        /// returns the ConsoleFEDataBlock, if any
+        console_fe,
+        ConsoleFeProps,
+        ConsoleFEDataBlock
+    );
+    block_accessor!(
+        /// returns the SpecialFolderDataBlock, if any
+        special_folder,
+        SpecialFolderProps,
+        SpecialFolderDataBlock
+    );
+    block_accessor!(
+        /// returns the DarwinDataBlock, if any
+        darwin,
+        DarwinProps,
+        DarwinDataBlock
+    );
+    block_accessor!(
+        /// returns the IconEnvironmentDataBlock, if any
+        icon_environment,
+        IconEnvironmentProps,
+        IconEnvironmentDataBlock
+    );
+    block_accessor!(
+        /// returns the ShimDataBlock, if any
+        shim,
+        ShimProps,
+        ShimDataBlock
+    );
+    block_accessor!(
+        /// returns the PropertyStoreDataBlock, if any
+        property_store,
+        PropertyStoreProps,
+        PropertyStoreDataBlock
+    );
+    block_accessor!(
+        /// returns the VistaAndAboveIDListDataBlock, if any
+        vista_and_above_id_list,
+        VistaAndAboveIdListProps,
+        VistaAndAboveIdListDataBlock
+    );
+    block_accessor!(
+        /// returns the KnownFolderDataBlock, if any
+        known_folder,
+        KnownFolderProps,
+        KnownFolderDataBlock
+    );
+    block_accessor!(
+        /// returns the ShellItemIdentifiers block, if any
+        shell_item_identifiers,
+        ShellItemIdentifiers,
+        ShellItemIdentifiers
+    );
+
+    /// inserts `block`. If there already is a block with the same
+    /// signature, it is replaced and returned.
+    ///
+    /// This does not update the [`LinkFlags`] of the
+    /// shell link, use [`ShellLink::insert_extra_data_block`](crate::ShellLink::insert_extra_data_block)
+    /// instead.
+    pub(crate) fn insert(&mut self, block: ExtraDataBlock) -> Option<ExtraDataBlock> {
+        match self
+            .blocks
+            .iter()
+            .position(|b| b.signature() == block.signature())
+        {
+            Some(index) => Some(std::mem::replace(&mut self.blocks[index], block)),
+            None => {
+                self.blocks.push(block);
+                #[cfg(feature = "binwrite")]
+                self.raw_blocks.push(None);
+                None
+            }
+        }
+    }
+
+    /// removes and returns the block which is identified by `signature`,
+    /// if any
+    pub(crate) fn remove(&mut self, signature: u32) -> Option<ExtraDataBlock> {
+        let index = self
+            .blocks
+            .iter()
+            .position(|block| block.signature() == signature)?;
+        #[cfg(feature = "binwrite")]
+        self.raw_blocks.remove(index);
+        Some(self.blocks.remove(index))
+    }
+}
+
+impl<'a> IntoIterator for &'a ExtraData {
+    type Item = &'a ExtraDataBlock;
+    type IntoIter = std::slice::Iter<'a, ExtraDataBlock>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.blocks.iter()
+    }
+}
+
+impl BinRead for ExtraData {
+    type Args<'a> = (&'static Encoding, &'a ParseContext);
+
+    /// In lenient mode, blocks which cannot be decoded are kept as
+    /// [`ExtraDataBlock::Unknown`], and reading stops at the first block
+    /// with an invalid size.
     fn read_options<R: std::io::Read + std::io::Seek>(
         reader: &mut R,
         _endian: binrw::Endian,
-        args: Self::Args<'_>,
+        (default_codepage, ctx): Self::Args<'_>,
     ) -> binrw::BinResult<Self> {
-        let mut blocks = Vec::new();
+        let mut extra_data = Self::default();
         loop {
-            let block_size: u32 = reader.read_le()?;
+            let start = reader.stream_position()?;
+            let block_size: u32 = match reader.read_le() {
+                Ok(block_size) => block_size,
+                Err(why) if ctx.can_recover() => {
+                    let error = ParseError::from_binrw(Section::ExtraData, &why, start);
+                    ctx.warn(
+                        start,
+                        format!("missing terminal block: {}", error.message()),
+                    );
+                    break;
+                }
+                Err(why) => return Err(why),
+            };
+
             if block_size == 0 {
                 break;
-            } else {
-                let block: ExtraDataBlock = reader.read_le_args((block_size, args.0))?;
-                blocks.push(block);
             }
+
+            ctx.check_limit(
+                "ExtraDataBlocks",
+                extra_data.blocks.len() + 1,
+                ctx.limits().max_extra_data_blocks,
+                start,
+            )?;
+            ctx.check_limit(
+                "BlockSize",
+                u64::from(block_size),
+                ctx.limits().max_size,
+                start,
+            )?;
+
+            let end = start + u64::from(block_size);
+            let block = match reader.read_le_args((block_size, default_codepage, ctx)) {
+                Ok(block) => block,
+                Err(why) if ctx.can_recover() && block_size >= 8 => {
+                    reader.seek(SeekFrom::Start(start + 4))?;
+                    let signature: u32 = reader.read_le()?;
+                    let error =
+                        ParseError::from_binrw(Section::ExtraDataBlock(signature), &why, start);
+                    ctx.warn(start, format!("unable to decode {error}"));
+                    let mut data = Vec::new();
+                    reader
+                        .by_ref()
+                        .take(u64::from(block_size - 8))
+                        .read_to_end(&mut data)?;
+                    ExtraDataBlock::Unknown { signature, data }
+                }
+                Err(_) if ctx.can_recover() => {
+                    ctx.warn(
+                        start,
+                        format!("invalid extra data block size: {block_size}"),
+                    );
+                    break;
+                }
+                Err(why) => {
+                    reader.seek(SeekFrom::Start(start + 4))?;
+                    let signature: u32 = reader.read_le()?;
+                    return Err(binrw::Error::Custom {
+                        pos: start,
+                        err: Box::new(ExtraDataBlockError {
+                            signature,
+                            error: why,
+                        }),
+                    });
+                }
+            };
+
+            // some blocks do not use all of their data, e.g. because of padding
+            reader.seek(SeekFrom::Start(end))?;
+            ctx.record_location(
+                format!("ExtraData/Block[{}]", extra_data.blocks.len()),
+                start,
+                end,
+            );
+
+            #[cfg(feature = "binwrite")]
+            extra_data.raw_blocks.push(RawBytes::capture(
+                reader,
+                start,
+                &block,
+                (default_codepage,),
+            )?);
+            extra_data.blocks.push(block);
         }
-        Ok(Self { blocks })
+        Ok(extra_data)
+    }
+}
+
+/// Writes all blocks, followed by the terminal block.
+#[cfg(feature = "binwrite")]
+impl BinWrite for ExtraData {
+    type Args<'a> = (&'static Encoding,);
+
+    fn write_options<W: std::io::Write + std::io::Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        args: Self::Args<'_>,
+    ) -> binrw::BinResult<()> {
+        for (index, block) in self.blocks.iter().enumerate() {
+            let raw = self.raw_blocks.get(index).and_then(Option::as_ref);
+            write_preserving(writer, block, args, raw)?;
+        }
+
+        // TerminalBlock
+        0u32.write_options(writer, endian, ())
     }
 }
@@ -1,9 +1,14 @@
 use binrw::BinRead;
+#[cfg(feature = "binwrite")]
+use binrw::BinWrite;
 use bitflags::bitflags;
 use encoding_rs::UTF_16LE;
-use getset::Getters;
+use getset::{Getters, Setters};
 
-use crate::{binread_flags::binread_flags, strings::FixedSizeString};
+use crate::{
+    binread_flags::binread_flags, error::FieldError, parse_options::ParseContext,
+    strings::FixedSizeString,
+};
 
 #[cfg(feature = "serde")]
 use serde::Serialize;
@@ -74,21 +79,29 @@ binread_flags!(FontFamilyFlags, u32);
 /// The ConsoleDataBlock structure specifies the display settings to use
 /// when a link target specifies an application that is run in a console
 /// window.
-#[derive(Clone, Debug, Getters, BinRead)]
+#[derive(Clone, Debug, Getters, Setters, BinRead)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
-#[br(import(block_size: u32), pre_assert(block_size == 0x0000_00CC))]
-#[get(get = "pub")]
+#[br(
+    import(block_size: u32, ctx: &ParseContext),
+    pre_assert(
+        block_size == 0x0000_00CC,
+        FieldError::new("BlockSize", "0xcc", format!("0x{block_size:x}"))
+    )
+)]
+#[getset(get = "pub", set = "pub")]
 #[allow(unused)]
 pub struct ConsoleDataBlock {
     /// A 16-bit, unsigned integer that specifies the fill attributes that
     /// control the foreground and background text colors in the console
     /// window. The following bit definitions can be combined to specify 16
     /// different values each for the foreground and background colors:
+    #[br(args_raw = Some(ctx))]
     fill_attributes: FillAttributeFlags,
     /// A 16-bit, unsigned integer that specifies the fill attributes that
     /// control the foreground and background text color in the console
     /// window popup. The values are the same as for the FillAttributes
     /// field.
+    #[br(args_raw = Some(ctx))]
     popup_fill_attributes: FillAttributeFlags,
     /// A 16-bit, signed integer that specifies the horizontal size (X axis),
     /// in characters, of the console window buffer.
@@ -123,6 +136,7 @@ pub struct ConsoleDataBlock {
     /// A 32-bit, unsigned integer that specifies the family of the font used
     /// in the console window. This value MUST be comprised of a font family
     /// and an optional font pitch.
+    #[br(args_raw = Some(ctx))]
     font_family: FontFamilyFlags,
     /// A 32-bit, unsigned integer that specifies the stroke weight of the font
     /// used in the console window.
@@ -169,3 +183,103 @@ pub struct ConsoleDataBlock {
     /// color for a character.
     color_table: [u32; 16],
 }
+
+impl Default for ConsoleDataBlock {
+    /// Create a ConsoleDataBlock with the settings of a new console window
+    /// on Windows 10
+    fn default() -> Self {
+        Self {
+            fill_attributes: FillAttributeFlags::FOREGROUND_BLUE
+                | FillAttributeFlags::FOREGROUND_GREEN
+                | FillAttributeFlags::FOREGROUND_RED,
+            popup_fill_attributes: FillAttributeFlags::FOREGROUND_BLUE
+                | FillAttributeFlags::FOREGROUND_RED
+                | FillAttributeFlags::BACKGROUND_BLUE
+                | FillAttributeFlags::BACKGROUND_GREEN
+                | FillAttributeFlags::BACKGROUND_RED
+                | FillAttributeFlags::BACKGROUND_INTENSITY,
+            screen_buffer_size_x: 120,
+            screen_buffer_size_y: 9001,
+            window_size_x: 120,
+            window_size_y: 30,
+            window_origin_x: 0,
+            window_origin_y: 0,
+            unused1: 0,
+            unused2: 0,
+            font_size: 0x0010_0000,
+            font_family: FontFamilyFlags::FF_MODERN | FontFamilyFlags::TMPF_TRUETYPE,
+            font_weight: 400,
+            face_name: "Consolas".to_string(),
+            cursor_size: 25,
+            full_screen: false,
+            quick_edit: true,
+            insert_mode: true,
+            auto_position: true,
+            history_buffer_size: 50,
+            number_of_history_buffers: 4,
+            history_no_dup: false,
+            color_table: [
+                0x000c_0c0c,
+                0x00da_3700,
+                0x000e_a113,
+                0x00dd_963a,
+                0x001f_0fc5,
+                0x0098_1788,
+                0x0000_9cc1,
+                0x00cc_cccc,
+                0x0076_7676,
+                0x00ff_783b,
+                0x000c_c616,
+                0x00d6_d661,
+                0x0056_48e7,
+                0x009e_00b4,
+                0x00a5_f1f9,
+                0x00f2_f2f2,
+            ],
+        }
+    }
+}
+
+#[cfg(feature = "binwrite")]
+impl BinWrite for ConsoleDataBlock {
+    type Args<'a> = ();
+
+    fn write_options<W: std::io::Write + std::io::Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        _args: Self::Args<'_>,
+    ) -> binrw::BinResult<()> {
+        self.fill_attributes.write_options(writer, endian, ())?;
+        self.popup_fill_attributes
+            .write_options(writer, endian, ())?;
+        self.screen_buffer_size_x
+            .write_options(writer, endian, ())?;
+        self.screen_buffer_size_y
+            .write_options(writer, endian, ())?;
+        self.window_size_x.write_options(writer, endian, ())?;
+        self.window_size_y.write_options(writer, endian, ())?;
+        self.window_origin_x.write_options(writer, endian, ())?;
+        self.window_origin_y.write_options(writer, endian, ())?;
+        self.unused1.write_options(writer, endian, ())?;
+        self.unused2.write_options(writer, endian, ())?;
+        self.font_size.write_options(writer, endian, ())?;
+        self.font_family.write_options(writer, endian, ())?;
+        self.font_weight.write_options(writer, endian, ())?;
+        FixedSizeString::from(self.face_name.clone()).write_options(
+            writer,
+            endian,
+            (64, UTF_16LE),
+        )?;
+        self.cursor_size.write_options(writer, endian, ())?;
+        u32::from(self.full_screen).write_options(writer, endian, ())?;
+        u32::from(self.quick_edit).write_options(writer, endian, ())?;
+        u32::from(self.insert_mode).write_options(writer, endian, ())?;
+        u32::from(self.auto_position).write_options(writer, endian, ())?;
+        self.history_buffer_size.write_options(writer, endian, ())?;
+        self.number_of_history_buffers
+            .write_options(writer, endian, ())?;
+        u32::from(self.history_no_dup).write_options(writer, endian, ())?;
+        self.color_table.write_options(writer, endian, ())
+    }
+}
@@ -1,4 +1,7 @@
+use crate::error::FieldError;
 use binrw::BinRead;
+#[cfg(feature = "binwrite")]
+use binrw::BinWrite;
 use getset::Getters;
 
 #[cfg(feature = "serde")]
@@ -9,8 +12,15 @@ use serde::Serialize;
 /// that is run in a console window.
 #[derive(Clone, Copy, Debug, BinRead, Getters)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
-#[br(import(block_size: u32), pre_assert(block_size == 0x0000_0000C))]
-#[get(get = "pub")]
+#[cfg_attr(feature = "binwrite", derive(BinWrite))]
+#[br(
+    import(block_size: u32),
+    pre_assert(
+        block_size == 0x0000_0000C,
+        FieldError::new("BlockSize", "0xc", format!("0x{block_size:x}"))
+    )
+)]
+#[getset(get = "pub")]
 #[allow(unused)]
 pub struct ConsoleFEDataBlock {
     /// A 32-bit, unsigned integer that specifies a code page language
@@ -1,10 +1,13 @@
 use binrw::BinRead;
+#[cfg(feature = "binwrite")]
+use binrw::BinWrite;
 use encoding_rs::{Encoding, UTF_16LE};
 use getset::Getters;
 
 #[cfg(feature = "serde")]
 use serde::Serialize;
 
+use crate::error::FieldError;
 use crate::strings::FixedSizeString;
 
 /// The DarwinDataBlock structure specifies an application identifier
@@ -12,8 +15,14 @@ use crate::strings::FixedSizeString;
 /// application when a shell link is activated.
 #[derive(Clone, Debug, BinRead, Getters)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
-#[br(import(block_size: u32, default_codepage: &'static Encoding), pre_assert(block_size == 0x0000_00314))]
-#[get(get = "pub")]
+#[br(
+    import(block_size: u32, default_codepage: &'static Encoding),
+    pre_assert(
+        block_size == 0x0000_00314,
+        FieldError::new("BlockSize", "0x314", format!("0x{block_size:x}"))
+    )
+)]
+#[getset(get = "pub")]
 #[allow(unused)]
 pub struct DarwinDataBlock {
     /// A NULL–terminated string, defined by the system default code
@@ -27,3 +36,38 @@ pub struct DarwinDataBlock {
     #[br(args(520, UTF_16LE), map=|s: FixedSizeString| if s.is_empty() {None} else {Some(s.to_string())})]
     darwin_data_unicode: Option<String>,
 }
+
+impl DarwinDataBlock {
+    /// Create a new `DarwinDataBlock`, which stores the application identifier both as
+    /// ANSI and as Unicode string
+    pub fn new(darwin_data: impl Into<String>) -> Self {
+        let darwin_data = darwin_data.into();
+        Self {
+            darwin_data_ansi: darwin_data.clone(),
+            darwin_data_unicode: Some(darwin_data),
+        }
+    }
+}
+
+#[cfg(feature = "binwrite")]
+impl BinWrite for DarwinDataBlock {
+    type Args<'a> = (&'static Encoding,);
+
+    fn write_options<W: std::io::Write + std::io::Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        args: Self::Args<'_>,
+    ) -> binrw::BinResult<()> {
+        FixedSizeString::from(self.darwin_data_ansi.clone()).write_options(
+            writer,
+            endian,
+            (260, args.0),
+        )?;
+        FixedSizeString::from(self.darwin_data_unicode.clone().unwrap_or_default()).write_options(
+            writer,
+            endian,
+            (520, UTF_16LE),
+        )
+    }
+}
@@ -1,10 +1,13 @@
 use binrw::BinRead;
+#[cfg(feature = "binwrite")]
+use binrw::BinWrite;
 use encoding_rs::{Encoding, UTF_16LE};
 use getset::Getters;
 
 #[cfg(feature = "serde")]
 use serde::Serialize;
 
+use crate::error::FieldError;
 use crate::strings::FixedSizeString;
 
 /// The EnvironmentVariableDataBlock structure specifies a path to
@@ -12,8 +15,14 @@ use crate::strings::FixedSizeString;
 /// a location that has a corresponding environment variable.
 #[derive(Clone, Debug, BinRead, Getters)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
-#[br(import(block_size: u32, default_codepage: &'static Encoding), pre_assert(block_size == 0x0000_0314))]
-#[get(get = "pub")]
+#[br(
+    import(block_size: u32, default_codepage: &'static Encoding),
+    pre_assert(
+        block_size == 0x0000_0314,
+        FieldError::new("BlockSize", "0x314", format!("0x{block_size:x}"))
+    )
+)]
+#[getset(get = "pub")]
 #[allow(unused)]
 pub struct EnvironmentVariableDataBlock {
     /// A NULL-terminated string, defined by the system default code
@@ -25,3 +34,48 @@ pub struct EnvironmentVariableDataBlock {
     #[br(args(520, UTF_16LE), map=|s: FixedSizeString| if s.is_empty() {None} else {Some(s.to_string())})]
     target_unicode: Option<String>,
 }
+
+impl EnvironmentVariableDataBlock {
+    /// Create a new `EnvironmentVariableDataBlock`, which stores the path both as
+    /// ANSI and as Unicode string
+    pub fn new(target: impl Into<String>) -> Self {
+        let target = target.into();
+        Self {
+            target_ansi: target.clone(),
+            target_unicode: Some(target),
+        }
+    }
+
+    /// Create a new `EnvironmentVariableDataBlock` with distinct ANSI and
+    /// Unicode paths, e.g. if `target` contains characters which cannot be
+    /// represented in the ANSI code page
+    pub fn with_ansi_target(target_ansi: impl Into<String>, target: impl Into<String>) -> Self {
+        Self {
+            target_ansi: target_ansi.into(),
+            target_unicode: Some(target.into()),
+        }
+    }
+}
+
+#[cfg(feature = "binwrite")]
+impl BinWrite for EnvironmentVariableDataBlock {
+    type Args<'a> = (&'static Encoding,);
+
+    fn write_options<W: std::io::Write + std::io::Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        args: Self::Args<'_>,
+    ) -> binrw::BinResult<()> {
+        FixedSizeString::from(self.target_ansi.clone()).write_options(
+            writer,
+            endian,
+            (260, args.0),
+        )?;
+        FixedSizeString::from(self.target_unicode.clone().unwrap_or_default()).write_options(
+            writer,
+            endian,
+            (520, UTF_16LE),
+        )
+    }
+}
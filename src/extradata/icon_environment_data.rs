@@ -1,10 +1,13 @@
 use binrw::BinRead;
+#[cfg(feature = "binwrite")]
+use binrw::BinWrite;
 use encoding_rs::{Encoding, UTF_16LE};
 use getset::Getters;
 
 #[cfg(feature = "serde")]
 use serde::Serialize;
 
+use crate::error::FieldError;
 use crate::strings::FixedSizeString;
 
 /// The IconEnvironmentDataBlock structure specifies the path to an
@@ -13,8 +16,14 @@ use crate::strings::FixedSizeString;
 /// vary but are expressed using environment variables.
 #[derive(Clone, Debug, BinRead, Getters)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
-#[br(import(block_size: u32, default_codepage: &'static Encoding), pre_assert(block_size == 0x0000_00314))]
-#[get(get = "pub")]
+#[br(
+    import(block_size: u32, default_codepage: &'static Encoding),
+    pre_assert(
+        block_size == 0x0000_00314,
+        FieldError::new("BlockSize", "0x314", format!("0x{block_size:x}"))
+    )
+)]
+#[getset(get = "pub")]
 #[allow(unused)]
 pub struct IconEnvironmentDataBlock {
     /// A NULL-terminated string, defined by the system default code
@@ -27,3 +36,38 @@ pub struct IconEnvironmentDataBlock {
     #[br(args(520, UTF_16LE), map=|s: FixedSizeString| if s.is_empty() {None} else {Some(s.to_string())})]
     target_unicode: Option<String>,
 }
+
+impl IconEnvironmentDataBlock {
+    /// Create a new `IconEnvironmentDataBlock`, which stores the path both as
+    /// ANSI and as Unicode string
+    pub fn new(target: impl Into<String>) -> Self {
+        let target = target.into();
+        Self {
+            target_ansi: target.clone(),
+            target_unicode: Some(target),
+        }
+    }
+}
+
+#[cfg(feature = "binwrite")]
+impl BinWrite for IconEnvironmentDataBlock {
+    type Args<'a> = (&'static Encoding,);
+
+    fn write_options<W: std::io::Write + std::io::Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        args: Self::Args<'_>,
+    ) -> binrw::BinResult<()> {
+        FixedSizeString::from(self.target_ansi.clone()).write_options(
+            writer,
+            endian,
+            (260, args.0),
+        )?;
+        FixedSizeString::from(self.target_unicode.clone().unwrap_or_default()).write_options(
+            writer,
+            endian,
+            (520, UTF_16LE),
+        )
+    }
+}
@@ -1,9 +1,12 @@
 use binrw::BinRead;
+#[cfg(feature = "binwrite")]
+use binrw::BinWrite;
 use getset::Getters;
 
 #[cfg(feature = "serde")]
 use serde::Serialize;
 
+use crate::error::FieldError;
 use crate::Guid;
 
 /// The KnownFolderDataBlock structure specifies the location of a
@@ -12,8 +15,15 @@ use crate::Guid;
 /// IDList can be translated when the link is loaded.
 #[derive(Clone, Copy, Debug, BinRead, Getters)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
-#[br(import(block_size: u32), pre_assert(block_size == 0x0000_0001C))]
-#[get(get = "pub")]
+#[cfg_attr(feature = "binwrite", derive(BinWrite))]
+#[br(
+    import(block_size: u32),
+    pre_assert(
+        block_size == 0x0000_0001C,
+        FieldError::new("BlockSize", "0x1c", format!("0x{block_size:x}"))
+    )
+)]
+#[getset(get = "pub")]
 #[allow(unused)]
 pub struct KnownFolderDataBlock {
     /// A value in GUID packet representation ([MS-DTYP] section
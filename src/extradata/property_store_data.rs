@@ -1,31 +1,68 @@
-use std::{fmt, mem::size_of};
+use std::{fmt, io::Cursor};
 
 #[cfg(feature = "serde")]
 use serde::Serialize;
 
-use binrw::BinRead;
+#[cfg(feature = "binwrite")]
+use binrw::BinWrite;
+use binrw::{BinRead, BinReaderExt};
+use encoding_rs::Encoding;
 use getset::Getters;
+#[allow(unused)]
+use log::{debug, error, info, trace, warn};
+
+use crate::error::FieldError;
+use crate::PropertyStore;
 
 /// A PropertyStoreDataBlock structure specifies a set of properties
 /// that can be used by applications to store extra data in the
 /// shell link.
-/// TODO: implement <https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-propstore/1eb58eb3-e7d8-4a09-ac0e-8bcb14b6fa0e>
 #[derive(Clone, BinRead, Getters)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
-#[br(import(block_size: u32), pre_assert(block_size >= 0x0000_000C))]
-#[get(get = "pub")]
+#[cfg_attr(feature = "binwrite", derive(BinWrite))]
+#[br(
+    import(block_size: u32, default_codepage: &'static Encoding),
+    pre_assert(
+        block_size >= 0x0000_000C,
+        FieldError::new("BlockSize", "at least 0xc", format!("0x{block_size:x}"))
+    )
+)]
+#[getset(get = "pub")]
 #[allow(unused)]
 pub struct PropertyStoreDataBlock {
     /// A serialized property storage structure ([MS-PROPSTORE] section 2.2).
-    #[br(count=block_size - u32::try_from(2*size_of::<u32>()).unwrap())]
+    #[br(count = block_size - 8)]
+    #[cfg_attr(feature = "serde", serde(skip))]
     property_store: Vec<u8>,
+
+    /// The decoded property store, or `None` if `property_store` could not
+    /// be decoded
+    #[br(calc = parse_property_store(&property_store, default_codepage))]
+    #[cfg_attr(feature = "binwrite", bw(ignore))]
+    properties: Option<PropertyStore>,
+}
+
+fn parse_property_store(data: &[u8], default_codepage: &'static Encoding) -> Option<PropertyStore> {
+    match Cursor::new(data).read_le_args((default_codepage,)) {
+        Ok(store) => Some(store),
+        Err(why) => {
+            debug!("unable to decode property store: {why}");
+            None
+        }
+    }
 }
 
 impl fmt::Debug for PropertyStoreDataBlock {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "PropertyStoreDataBlock {{ property_store: (serialized property storage structure) }}"
-        )
+        match &self.properties {
+            Some(properties) => f
+                .debug_struct("PropertyStoreDataBlock")
+                .field("properties", properties)
+                .finish(),
+            None => write!(
+                f,
+                "PropertyStoreDataBlock {{ property_store: (serialized property storage structure) }}"
+            ),
+        }
     }
 }
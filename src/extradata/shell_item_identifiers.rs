@@ -1,17 +1,30 @@
 use binrw::BinRead;
+#[cfg(feature = "binwrite")]
+use binrw::BinWrite;
+use encoding_rs::Encoding;
 use getset::Getters;
 use serde::Serialize;
 
+use crate::error::FieldError;
 use crate::generic_types::idlist::IdList;
+use crate::parse_options::ParseContext;
 
 #[derive(Clone, Debug, BinRead, Getters)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
-#[br(import(block_size: u32), pre_assert(block_size != 10))]
-#[get(get = "pub")]
+#[cfg_attr(feature = "binwrite", derive(BinWrite))]
+#[br(
+    import(block_size: u32, default_codepage: &'static Encoding, ctx: &ParseContext),
+    pre_assert(
+        block_size != 10,
+        FieldError::new("BlockSize", "not 0xa", format!("0x{block_size:x}"))
+    )
+)]
+#[getset(get = "pub")]
 #[allow(unused)]
 /// Contains a list of item identifiers.
 /// <https://learn.microsoft.com/en-us/windows/win32/api/shtypes/ns-shtypes-itemidlist>
 pub struct ShellItemIdentifiers {
-    #[br(args((block_size - 8).try_into().unwrap()))]
+    /// An IDList structure (section 2.2.1).
+    #[br(args(block_size.saturating_sub(8), default_codepage, ctx))]
     items: IdList,
 }
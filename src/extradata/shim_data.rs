@@ -1,24 +1,61 @@
-use std::mem::size_of;
-
 use binrw::BinRead;
+#[cfg(feature = "binwrite")]
+use binrw::BinWrite;
 use encoding_rs::UTF_16LE;
 use getset::Getters;
 
 #[cfg(feature = "serde")]
 use serde::Serialize;
 
+use crate::error::FieldError;
 use crate::strings::FixedSizeString;
 
 /// The ShimDataBlock structure specifies the name of a shim that can
 /// be applied when activating a link target.
 #[derive(Clone, Debug, BinRead, Getters)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
-#[br(import(block_size: u32), pre_assert(block_size >= 0x0000_00088))]
-#[get(get = "pub")]
+#[br(
+    import(block_size: u32),
+    pre_assert(
+        block_size >= 0x0000_00088,
+        FieldError::new("BlockSize", "at least 0x88", format!("0x{block_size:x}"))
+    )
+)]
+#[getset(get = "pub")]
 #[allow(unused)]
 pub struct ShimDataBlock {
     /// A Unicode string that specifies the name of a shim layer to apply
     /// to a link target when it is being activated.
-    #[br(args(usize::try_from(block_size).unwrap() - 2*size_of::<u32>(), UTF_16LE), map=|s:FixedSizeString| s.to_string())]
+    #[br(args((block_size - 8) as usize, UTF_16LE), map=|s:FixedSizeString| s.to_string())]
     layer_name: String,
 }
+
+impl ShimDataBlock {
+    /// Create a new `ShimDataBlock` for the shim layer `layer_name`
+    pub fn new(layer_name: impl Into<String>) -> Self {
+        Self {
+            layer_name: layer_name.into(),
+        }
+    }
+}
+
+/// writes the layer name as NULL-terminated Unicode string, which is padded
+/// to the minimum block size of 0x88 bytes
+#[cfg(feature = "binwrite")]
+impl BinWrite for ShimDataBlock {
+    type Args<'a> = ();
+
+    fn write_options<W: std::io::Write + std::io::Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        _args: Self::Args<'_>,
+    ) -> binrw::BinResult<()> {
+        let size = ((self.layer_name.encode_utf16().count() + 1) * 2).max(0x80);
+        FixedSizeString::from(self.layer_name.clone()).write_options(
+            writer,
+            endian,
+            (size, UTF_16LE),
+        )
+    }
+}
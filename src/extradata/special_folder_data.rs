@@ -1,4 +1,7 @@
+use crate::error::FieldError;
 use binrw::BinRead;
+#[cfg(feature = "binwrite")]
+use binrw::BinWrite;
 use getset::Getters;
 
 #[cfg(feature = "serde")]
@@ -10,8 +13,15 @@ use serde::Serialize;
 /// IDList can be translated when the link is loaded.
 #[derive(Clone, Copy, Debug, BinRead, Getters)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
-#[br(import(block_size: u32), pre_assert(block_size == 0x0000_00010))]
-#[get(get = "pub")]
+#[cfg_attr(feature = "binwrite", derive(BinWrite))]
+#[br(
+    import(block_size: u32),
+    pre_assert(
+        block_size == 0x0000_00010,
+        FieldError::new("BlockSize", "0x10", format!("0x{block_size:x}"))
+    )
+)]
+#[getset(get = "pub")]
 #[allow(unused)]
 pub struct SpecialFolderDataBlock {
     /// A 32-bit, unsigned integer that specifies the folder integer ID.
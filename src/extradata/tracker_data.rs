@@ -1,28 +1,42 @@
 use binrw::BinRead;
+#[cfg(feature = "binwrite")]
+use binrw::BinWrite;
+use chrono::NaiveDateTime;
 use encoding_rs::Encoding;
+use getset::Getters;
 
 #[cfg(feature = "serde")]
 use serde::Serialize;
 
-use crate::{strings::FixedSizeString, Guid};
+use crate::parse_options::read_constant;
+use crate::{error::FieldError, strings::FixedSizeString, Guid};
 
 /// The TrackerDataBlock structure specifies data that can be used to
 /// resolve a link target if it is not found in its original location
 /// when the link is resolved. This data is passed to the Link
 /// Tracking service [MS-DLTW] to find the link target.
-#[derive(Clone, Debug, BinRead)]
+#[derive(Clone, Debug, BinRead, Getters)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
-#[br(import(block_size: u32, default_codepage: &'static Encoding), pre_assert(block_size == 0x0000_00060))]
+#[br(
+    import(block_size: u32, default_codepage: &'static Encoding),
+    pre_assert(
+        block_size == 0x0000_00060,
+        FieldError::new("BlockSize", "0x60", format!("0x{block_size:x}"))
+    )
+)]
+#[getset(get = "pub")]
 #[allow(unused)]
 pub struct TrackerDataBlock {
     /// A 32-bit, unsigned integer that specifies the size of the rest of the
     /// TrackerDataBlock structure, including this Length field. This value
     /// MUST be 0x00000058.
-    #[br(assert(length == 0x00000058))]
+    #[br(parse_with = read_constant, args("Length", 0x0000_0058, |v| format!("0x{v:x}")))]
+    #[getset(skip)]
     length: u32,
 
     /// A 32-bit, unsigned integer. This value MUST be 0x00000000   
-    #[br(assert(version == 0x00000000))]
+    #[br(parse_with = read_constant, args("Version", 0, u32::to_string))]
+    #[getset(skip)]
     version: u32,
 
     /// A NULL–terminated character string, as defined by the system default
@@ -32,9 +46,76 @@ pub struct TrackerDataBlock {
     machine_id: String,
     /// Two values in GUID packet representation ([MS-DTYP] section 2.3.4.2)
     /// that are used to find the link target with the Link Tracking service,
-    /// as described in [MS-DLTW].
+    /// as described in [MS-DLTW]. The first value is the volume ID, the
+    /// second value is the object ID.
     droid: [Guid; 2],
     /// Two values in GUID packet representation that are used to find the
     /// link target with the Link Tracking service
     droid_birth: [Guid; 2],
 }
+
+impl TrackerDataBlock {
+    /// returns the volume ID of the droid, which identifies the volume
+    /// where the link target was last known to reside
+    pub fn volume_id(&self) -> &Guid {
+        &self.droid[0]
+    }
+
+    /// returns the object ID of the droid, which identifies the link target
+    /// on its volume
+    pub fn object_id(&self) -> &Guid {
+        &self.droid[1]
+    }
+
+    /// returns the volume ID of the birth droid, which identifies the
+    /// volume where the link target was created
+    pub fn birth_volume_id(&self) -> &Guid {
+        &self.droid_birth[0]
+    }
+
+    /// returns the object ID of the birth droid, which was assigned to
+    /// the link target when it was created
+    pub fn birth_object_id(&self) -> &Guid {
+        &self.droid_birth[1]
+    }
+
+    /// returns the time when the object ID of the link target was
+    /// created, if the object ID is a version 1 GUID
+    pub fn creation_time(&self) -> Option<NaiveDateTime> {
+        self.object_id().timestamp()
+    }
+
+    /// returns the clock sequence of the object ID, if the object ID is a
+    /// version 1 GUID
+    pub fn clock_sequence(&self) -> Option<u16> {
+        self.object_id().clock_sequence()
+    }
+
+    /// returns the MAC address of the machine which created the object ID,
+    /// if the object ID is a version 1 GUID
+    pub fn mac_address(&self) -> Option<String> {
+        self.object_id().mac_address()
+    }
+}
+
+#[cfg(feature = "binwrite")]
+impl BinWrite for TrackerDataBlock {
+    type Args<'a> = (&'static Encoding,);
+
+    fn write_options<W: std::io::Write + std::io::Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        args: Self::Args<'_>,
+    ) -> binrw::BinResult<()> {
+        0x0000_0058u32.write_options(writer, endian, ())?;
+        0x0000_0000u32.write_options(writer, endian, ())?;
+        FixedSizeString::from(self.machine_id.clone()).write_options(
+            writer,
+            endian,
+            (16, args.0),
+        )?;
+        self.droid.write_options(writer, endian, ())?;
+        self.droid_birth.write_options(writer, endian, ())
+    }
+}
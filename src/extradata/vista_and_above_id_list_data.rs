@@ -1,11 +1,14 @@
-use std::mem::size_of;
-
 use binrw::BinRead;
+#[cfg(feature = "binwrite")]
+use binrw::BinWrite;
+use encoding_rs::Encoding;
 use getset::Getters;
 
 #[cfg(feature = "serde")]
 use serde::Serialize;
 
+use crate::error::FieldError;
+use crate::parse_options::ParseContext;
 use crate::IdList;
 
 /// The VistaAndAboveIDListDataBlock structure specifies an alternate
@@ -13,11 +16,18 @@ use crate::IdList;
 /// (section 2.2) on platforms that support it.
 #[derive(Clone, Debug, BinRead, Getters)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
-#[br(import(block_size: u32), pre_assert(block_size >= 0x0000_0000A))]
-#[get(get = "pub")]
+#[cfg_attr(feature = "binwrite", derive(BinWrite))]
+#[br(
+    import(block_size: u32, default_codepage: &'static Encoding, ctx: &ParseContext),
+    pre_assert(
+        block_size >= 0x0000_0000A,
+        FieldError::new("BlockSize", "at least 0xa", format!("0x{block_size:x}"))
+    )
+)]
+#[getset(get = "pub")]
 #[allow(unused)]
 pub struct VistaAndAboveIdListDataBlock {
     /// An IDList structure (section 2.2.1).
-    #[br(args(u16::try_from(block_size).unwrap() - u16::try_from(2*size_of::<u32>()).unwrap()))]
+    #[br(args(block_size - 8, default_codepage, ctx))]
     id_list: IdList,
 }
@@ -0,0 +1,131 @@
+use std::fmt;
+
+use binrw::{BinRead, BinWrite};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// A date and time in MS-DOS format, as it is used in FAT file systems and in
+/// shell items. It consists of a 16-bit date value, followed by a 16-bit time
+/// value. The resolution is two seconds.
+///
+/// <https://learn.microsoft.com/en-us/windows/win32/api/winbase/nf-winbase-dosdatetimetofiletime>
+#[derive(Clone, Copy, Default, Eq, PartialEq, BinRead, BinWrite)]
+pub struct DosDateTime {
+    date: u16,
+    time: u16,
+}
+
+impl DosDateTime {
+    /// Create a new `DosDateTime` from the raw date and time values
+    pub fn new(date: u16, time: u16) -> Self {
+        Self { date, time }
+    }
+
+    /// the raw 16-bit date value
+    pub fn date(&self) -> u16 {
+        self.date
+    }
+
+    /// the raw 16-bit time value
+    pub fn time(&self) -> u16 {
+        self.time
+    }
+
+    /// Convert the `DosDateTime` object to a [`NaiveDateTime`]. Returns `None`
+    /// if no date is set or if the value is not a valid date.
+    pub fn datetime(&self) -> Option<NaiveDateTime> {
+        if self.date == 0 && self.time == 0 {
+            return None;
+        }
+
+        let year = 1980 + i32::from(self.date >> 9);
+        let month = u32::from((self.date >> 5) & 0x0f);
+        let day = u32::from(self.date & 0x1f);
+        let hour = u32::from(self.time >> 11);
+        let minute = u32::from((self.time >> 5) & 0x3f);
+        let second = u32::from(self.time & 0x1f) * 2;
+
+        NaiveDate::from_ymd_opt(year, month, day)?.and_hms_opt(hour, minute, second)
+    }
+
+    /// Convert a [`NaiveDateTime`] to a `DosDateTime`, rounding down to two
+    /// seconds. Returns `None` if the year is before 1980 or after 2107.
+    pub fn from_datetime(datetime: NaiveDateTime) -> Option<Self> {
+        let year = u16::try_from(datetime.year() - 1980).ok()?;
+        if year > 127 {
+            return None;
+        }
+        // all other values fit into 16 bits
+        let date = (year << 9) | ((datetime.month() as u16) << 5) | datetime.day() as u16;
+        let time = ((datetime.hour() as u16) << 11)
+            | ((datetime.minute() as u16) << 5)
+            | (datetime.second() as u16 / 2);
+        Some(Self { date, time })
+    }
+}
+
+impl fmt::Debug for DosDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.datetime() {
+            Some(datetime) => write!(f, "{datetime}"),
+            None => write!(f, "DosDateTime(0x{:04x}, 0x{:04x})", self.date, self.time),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Serialize for DosDateTime {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self.datetime() {
+            Some(datetime) => serializer.serialize_str(&format!("{datetime}")),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::DosDateTime;
+
+    #[test]
+    fn test_datetime() {
+        let datetime = DosDateTime::new(0x392c, 0xa369).datetime().unwrap();
+        assert_eq!(
+            datetime,
+            NaiveDate::from_ymd_opt(2008, 9, 12)
+                .unwrap()
+                .and_hms_opt(20, 27, 18)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_from_datetime() {
+        let datetime = NaiveDate::from_ymd_opt(2008, 9, 12)
+            .unwrap()
+            .and_hms_opt(20, 27, 19)
+            .unwrap();
+        assert_eq!(
+            DosDateTime::from_datetime(datetime),
+            Some(DosDateTime::new(0x392c, 0xa369))
+        );
+
+        let datetime = NaiveDate::from_ymd_opt(1979, 12, 31)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq!(DosDateTime::from_datetime(datetime), None);
+    }
+
+    #[test]
+    fn test_empty_datetime() {
+        assert_eq!(DosDateTime::default().datetime(), None);
+    }
+}
@@ -1,7 +1,10 @@
+use std::cmp::Ordering;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use binrw::{BinRead, BinReaderExt, BinWrite};
-use chrono::NaiveDateTime;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeDelta, Utc};
 
 #[cfg(feature = "serde")]
 use serde::Serialize;
@@ -63,38 +66,145 @@ impl Serialize for FileTime {
     }
 }
 
+/// the number of 100-nanosecond intervals per second
+const INTERVALS_PER_SECOND: u64 = 10_000_000;
+
+/// the number of seconds between January 1, 1601 and January 1, 1970
+const SECONDS_BEFORE_UNIX_EPOCH: u64 = 11_644_473_600;
+
 impl FileTime {
-    /// Convert the `FileTime` object to a [[]]
+    /// Create a new `FileTime` object representing now.
+    pub fn now() -> Self {
+        Self::from(SystemTime::now())
+    }
+
+    /// Create a `FileTime` from the number of 100-nanosecond intervals
+    /// since January 1, 1601 (UTC)
+    pub fn from_raw(raw: u64) -> Self {
+        // eight bytes are always a valid timestamp
+        let timestamp = WinTimestamp::new(&raw.to_le_bytes()).unwrap();
+        Self(timestamp, raw)
+    }
+
+    /// returns the number of 100-nanosecond intervals since January 1, 1601
+    /// (UTC)
+    pub fn raw(&self) -> u64 {
+        self.1
+    }
+
+    /// returns `true` if no time is set, which is represented by zero
+    pub fn is_zero(&self) -> bool {
+        self.1 == 0
+    }
+
+    /// Convert the `FileTime` object to a [`NaiveDateTime`] in UTC
     pub fn datetime(&self) -> NaiveDateTime {
-        self.0.to_datetime().naive_utc()
+        self.datetime_utc().naive_utc()
     }
 
-    /*
-    /// Create a new `FileTime` object representing now.
-    pub fn now() -> Self {
-        Self::from(chrono::Local::now().naive_local())
+    /// Convert the `FileTime` object to a [`DateTime<Utc>`]
+    pub fn datetime_utc(&self) -> DateTime<Utc> {
+        let seconds = (self.1 / INTERVALS_PER_SECOND) as i64;
+        let nanoseconds = (self.1 % INTERVALS_PER_SECOND * 100) as i64;
+        // the range of chrono exceeds the range of FILETIME
+        file_time_epoch() + TimeDelta::seconds(seconds) + TimeDelta::nanoseconds(nanoseconds)
+    }
+
+    /// Convert the `FileTime` object to a [`SystemTime`]
+    pub fn system_time(&self) -> SystemTime {
+        let since_epoch = Duration::new(
+            self.1 / INTERVALS_PER_SECOND,
+            (self.1 % INTERVALS_PER_SECOND * 100) as u32,
+        );
+        let epoch_offset = Duration::from_secs(SECONDS_BEFORE_UNIX_EPOCH);
+        if since_epoch >= epoch_offset {
+            UNIX_EPOCH + (since_epoch - epoch_offset)
+        } else {
+            UNIX_EPOCH - (epoch_offset - since_epoch)
+        }
     }
-     */
+}
+
+/// returns January 1, 1601 (UTC), which is the epoch of FILETIME
+fn file_time_epoch() -> DateTime<Utc> {
+    NaiveDate::from_ymd_opt(1601, 1, 1)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .unwrap_or_default()
+        .and_utc()
 }
 
 impl Default for FileTime {
     fn default() -> Self {
-        let raw = 0u64;
-        let timestamp = WinTimestamp::new(&raw.to_le_bytes()).unwrap();
-        Self(timestamp, raw)
+        Self::from_raw(0)
+    }
+}
+
+impl PartialEq for FileTime {
+    fn eq(&self, other: &Self) -> bool {
+        self.1 == other.1
+    }
+}
+
+impl Eq for FileTime {}
+
+impl PartialOrd for FileTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FileTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.1.cmp(&other.1)
+    }
+}
+
+impl Hash for FileTime {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.1.hash(state);
+    }
+}
+
+impl From<u64> for FileTime {
+    fn from(raw: u64) -> Self {
+        Self::from_raw(raw)
+    }
+}
+
+impl From<DateTime<Utc>> for FileTime {
+    /// Convert a date and time to a `FileTime`. Dates before January 1,
+    /// 1601 are converted to zero, dates after the range of FILETIME are
+    /// converted to its maximum.
+    fn from(value: DateTime<Utc>) -> Self {
+        let duration = value - file_time_epoch();
+        let intervals = i128::from(duration.num_seconds()) * i128::from(INTERVALS_PER_SECOND)
+            + i128::from(duration.subsec_nanos() / 100);
+        let raw = u64::try_from(intervals.max(0)).unwrap_or(u64::MAX);
+        Self::from_raw(raw)
     }
 }
 
-/*
 impl From<NaiveDateTime> for FileTime {
+    /// Convert a date and time in UTC to a `FileTime`, like a
+    /// [`DateTime<Utc>`]
     fn from(value: NaiveDateTime) -> Self {
-        let duration = value - Self::epoch();
-        let val = duration.num_microseconds().unwrap() * 10;
-        let val = val as u64;
-        Self::from(val)
+        Self::from(value.and_utc())
+    }
+}
+
+impl From<SystemTime> for FileTime {
+    /// Convert a system time to a `FileTime`. Times before January 1, 1601
+    /// are converted to zero.
+    fn from(value: SystemTime) -> Self {
+        let epoch_offset = i128::from(SECONDS_BEFORE_UNIX_EPOCH) * 1_000_000_000;
+        let nanoseconds = match value.duration_since(UNIX_EPOCH) {
+            Ok(duration) => epoch_offset + duration.as_nanos() as i128,
+            Err(why) => epoch_offset - why.duration().as_nanos() as i128,
+        };
+        let raw = u64::try_from((nanoseconds / 100).max(0)).unwrap_or(u64::MAX);
+        Self::from_raw(raw)
     }
 }
- */
 
 impl From<FileTime> for u64 {
     fn from(val: FileTime) -> Self {
@@ -102,15 +212,100 @@ impl From<FileTime> for u64 {
     }
 }
 
+impl From<FileTime> for NaiveDateTime {
+    fn from(val: FileTime) -> Self {
+        val.datetime()
+    }
+}
+
+impl From<FileTime> for DateTime<Utc> {
+    fn from(val: FileTime) -> Self {
+        val.datetime_utc()
+    }
+}
+
+impl From<FileTime> for SystemTime {
+    fn from(val: FileTime) -> Self {
+        val.system_time()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::Cursor;
 
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
     use binrw::{BinReaderExt, BinWrite};
+    use chrono::{DateTime, NaiveDate, Utc};
     use winstructs::timestamp::WinTimestamp;
 
     use super::FileTime;
 
+    /// 2013-10-19 12:16:53.2760403 UTC
+    const RAW: u64 = 0x01ce_ccc5_188b_c753;
+
+    #[test]
+    fn test_chrono_conversions() {
+        let datetime = NaiveDate::from_ymd_opt(2013, 10, 19)
+            .unwrap()
+            .and_hms_nano_opt(12, 16, 53, 276_040_300)
+            .unwrap();
+        let file_time = FileTime::from(RAW);
+        assert_eq!(file_time.datetime(), datetime);
+        assert_eq!(FileTime::from(datetime), file_time);
+        assert_eq!(FileTime::from(datetime.and_utc()), file_time);
+        assert_eq!(DateTime::<Utc>::from(file_time.clone()), datetime.and_utc());
+        assert_eq!(u64::from(file_time), RAW);
+
+        let before_epoch = NaiveDate::from_ymd_opt(1600, 12, 31)
+            .unwrap()
+            .and_hms_opt(23, 59, 59)
+            .unwrap();
+        assert!(FileTime::from(before_epoch).is_zero());
+        assert_eq!(
+            FileTime::default().datetime(),
+            NaiveDate::from_ymd_opt(1601, 1, 1)
+                .unwrap()
+                .and_hms_opt(0, 0, 0)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_system_time_conversions() {
+        let unix_epoch = FileTime::from(116_444_736_000_000_000);
+        assert_eq!(FileTime::from(UNIX_EPOCH), unix_epoch);
+        assert_eq!(unix_epoch.system_time(), UNIX_EPOCH);
+
+        let system_time = UNIX_EPOCH + Duration::new(1_382_185_013, 276_040_300);
+        assert_eq!(FileTime::from(system_time).raw(), RAW);
+        assert_eq!(SystemTime::from(FileTime::from(RAW)), system_time);
+
+        let before_unix_epoch = UNIX_EPOCH - Duration::from_secs(86400);
+        assert_eq!(
+            FileTime::from(before_unix_epoch).raw(),
+            116_444_736_000_000_000 - 86400 * 10_000_000
+        );
+        assert_eq!(
+            FileTime::from(0).system_time(),
+            UNIX_EPOCH - Duration::from_secs(11_644_473_600)
+        );
+
+        assert!(!FileTime::now().is_zero());
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(FileTime::from(1) < FileTime::from(2));
+        assert_eq!(
+            FileTime::from(RAW).max(FileTime::default()),
+            FileTime::from(RAW)
+        );
+        assert!(FileTime::default().is_zero());
+        assert!(!FileTime::from(RAW).is_zero());
+    }
+
     #[test]
     fn test_guid_be() {
         let mut cursor = Cursor::new([0u8; 16]);
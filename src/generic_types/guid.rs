@@ -1,6 +1,7 @@
 use std::fmt::Display;
 
 use binrw::{BinRead, BinWrite};
+use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
 #[cfg(feature = "serde")]
 use serde::Serialize;
 use uuid::{Builder, Uuid};
@@ -59,6 +60,70 @@ impl BinWrite for Guid {
     }
 }
 
+impl Guid {
+    /// returns the version of this GUID, e.g. 1 for time-based GUIDs
+    pub fn version(&self) -> usize {
+        self.0.get_version_num()
+    }
+
+    /// returns the timestamp of a version 1 GUID, which is the time when
+    /// the GUID was created. Returns `None` for all other versions.
+    pub fn timestamp(&self) -> Option<NaiveDateTime> {
+        if self.version() != 1 {
+            return None;
+        }
+
+        let (time_low, time_mid, time_hi_and_version, _) = self.0.as_fields();
+        let ticks = (u64::from(time_hi_and_version & 0x0fff) << 48)
+            | (u64::from(time_mid) << 32)
+            | u64::from(time_low);
+
+        // the timestamp counts 100-nanosecond intervals since the beginning
+        // of the gregorian calendar
+        let epoch = NaiveDate::from_ymd_opt(1582, 10, 15)?.and_hms_opt(0, 0, 0)?;
+        epoch.checked_add_signed(
+            TimeDelta::microseconds(i64::try_from(ticks / 10).ok()?)
+                + TimeDelta::nanoseconds(i64::try_from(ticks % 10).ok()? * 100),
+        )
+    }
+
+    /// returns the clock sequence of a version 1 GUID. Returns `None` for all
+    /// other versions.
+    pub fn clock_sequence(&self) -> Option<u16> {
+        if self.version() != 1 {
+            return None;
+        }
+
+        let d4 = self.0.as_fields().3;
+        Some((u16::from(d4[0] & 0x3f) << 8) | u16::from(d4[1]))
+    }
+
+    /// returns the node of a version 1 GUID, which usually is the MAC address
+    /// of the machine which created the GUID. Returns `None` for all other
+    /// versions.
+    pub fn node(&self) -> Option<[u8; 6]> {
+        if self.version() != 1 {
+            return None;
+        }
+
+        let d4 = self.0.as_fields().3;
+        let mut node = [0; 6];
+        node.copy_from_slice(&d4[2..]);
+        Some(node)
+    }
+
+    /// returns the node of a version 1 GUID, formatted as MAC address, e.g.
+    /// `00:13:72:16:87:4a`
+    pub fn mac_address(&self) -> Option<String> {
+        self.node().map(|node| {
+            node.iter()
+                .map(|b| format!("{b:02x}"))
+                .collect::<Vec<_>>()
+                .join(":")
+        })
+    }
+}
+
 impl Display for Guid {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.0.fmt(f)
@@ -95,6 +160,22 @@ mod tests {
         assert_eq!(input, output);
     }
 
+    #[test]
+    fn test_guid_v1() {
+        let guid = Guid(uuid!("7bcd46ec-7f22-11dd-9499-00137216874a"));
+        assert_eq!(guid.version(), 1);
+        assert_eq!(guid.clock_sequence(), Some(0x1499));
+        assert_eq!(guid.mac_address().as_deref(), Some("00:13:72:16:87:4a"));
+        assert_eq!(
+            guid.timestamp().unwrap().to_string(),
+            "2008-09-10 10:23:17.364913200"
+        );
+
+        let guid = Guid(uuid!("94c77840-fa47-46c7-b356-5c2dc6b6d115"));
+        assert_eq!(guid.timestamp(), None);
+        assert_eq!(guid.node(), None);
+    }
+
     #[test]
     fn test_guid_le() {
         let mut cursor = Cursor::new([0u8; 16]);
@@ -1,9 +1,31 @@
+use std::io::SeekFrom;
+
+#[cfg(feature = "binwrite")]
+use binrw::BinWrite;
 use binrw::{BinRead, BinReaderExt};
+use encoding_rs::Encoding;
 use getset::Getters;
 use log::trace;
 use serde::Serialize;
 
-use crate::itemid::ItemID;
+use crate::{
+    error::FieldError,
+    itemid::ItemID,
+    parse_options::ParseContext,
+    shell_item::{
+        file_entry::{FileEntryMetadata, FileEntryShellItem},
+        root_folder::{RootFolderShellItem, MY_COMPUTER_CLSID},
+        volume::VolumeShellItem,
+        ShellItem,
+    },
+    BuildError, Guid,
+};
+
+/// the characters which must not be used in the name of a file or directory
+const INVALID_NAME_CHARACTERS: &str = "<>:\"/\\|?*";
+
+/// the maximum length of the name of a file or directory
+const MAX_NAME_LENGTH: usize = 255;
 
 /// The stored IDList structure specifies the format of a persisted item ID list.
 #[derive(Clone, Debug, Default, Getters)]
@@ -15,15 +37,15 @@ pub struct IdList {
 }
 
 impl BinRead for IdList {
-    type Args<'a> = (u16,);
+    type Args<'a> = (u32, &'static Encoding, &'a ParseContext);
 
     fn read_options<R: std::io::Read + std::io::Seek>(
         reader: &mut R,
         _endian: binrw::Endian,
-        args: Self::Args<'_>,
+        (size, default_codepage, ctx): Self::Args<'_>,
     ) -> binrw::BinResult<Self> {
         let mut item_id_list = Vec::new();
-        let mut bytes_to_read = args.0;
+        let mut bytes_to_read = size;
         trace!("ID List size: {bytes_to_read}");
         while bytes_to_read > 0 {
             // an IDList contains any number of ItemID structures,
@@ -37,18 +59,230 @@ impl BinRead for IdList {
                 });
             }
 
-            let item_id: ItemID = reader.read_le()?;
+            ctx.check_limit(
+                "ItemIDs",
+                item_id_list.len() + 1,
+                ctx.limits().max_item_ids,
+                reader.stream_position()?,
+            )?;
+            let pos = reader.stream_position()?;
+            let item_id: ItemID = reader.read_le_args((default_codepage,))?;
 
-            // if the item has a size of zero, then this
-            // is the terminator
-            if bytes_to_read == 2 && *item_id.size() == 0 {
+            // if the item has a size of zero, then this is the terminator,
+            // which must be the last item of the list
+            if *item_id.size() == 0 {
+                ctx.check(bytes_to_read == 2, pos, || {
+                    FieldError::new(
+                        "TerminalID",
+                        "at the end of the IDList",
+                        format!("{} bytes before the end", bytes_to_read - 2),
+                    )
+                })?;
+                reader.seek(SeekFrom::Start(pos + u64::from(bytes_to_read)))?;
                 break;
             }
 
-            bytes_to_read -= item_id.size();
+            bytes_to_read = match bytes_to_read.checked_sub(u32::from(*item_id.size())) {
+                Some(bytes_to_read) => bytes_to_read,
+                None => {
+                    return Err(binrw::error::Error::AssertFail {
+                        pos: reader.stream_position()?,
+                        message: format!(
+                            "ItemID of {} bytes exceeds the size of the IDList",
+                            item_id.size()
+                        ),
+                    })
+                }
+            };
             item_id_list.push(item_id);
         }
 
         Ok(Self { item_id_list })
     }
 }
+
+#[cfg(feature = "binwrite")]
+impl BinWrite for IdList {
+    type Args<'a> = ();
+
+    fn write_options<W: std::io::Write + std::io::Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        _args: Self::Args<'_>,
+    ) -> binrw::BinResult<()> {
+        for item_id in self.item_id_list.iter() {
+            item_id.write_options(writer, endian, ())?;
+        }
+
+        // TerminalID
+        0u16.write_options(writer, endian, ())
+    }
+}
+
+impl IdList {
+    /// creates the IDList which Windows creates for the absolute path `path`,
+    /// like `C:\Users\Public\readme.txt`. The list consists of the "My
+    /// Computer" shell folder, the drive, and a file entry for each
+    /// component of the path. The last component is a file, unless the path
+    /// ends with a backslash.
+    ///
+    /// The file system is not accessed, so the file entries contain neither
+    /// timestamps nor sizes, and their short names are generated as if there
+    /// were no other files with similar names.
+    pub fn from_windows_path(path: &str) -> Result<Self, BuildError> {
+        let is_directory = path.ends_with('\\');
+        Self::from_windows_path_with(path, &FileEntryMetadata::new(is_directory))
+    }
+
+    /// creates the IDList for the absolute path `path`, see
+    /// [`IdList::from_windows_path`]. The file entry of the last component
+    /// uses `metadata`.
+    pub(crate) fn from_windows_path_with(
+        path: &str,
+        metadata: &FileEntryMetadata,
+    ) -> Result<Self, BuildError> {
+        let invalid_path = || BuildError::InvalidTargetPath(path.to_string());
+
+        let drive_letter = windows_drive_letter(path).ok_or_else(invalid_path)?;
+        let relative_path = &path[3..];
+        let relative_path = relative_path.strip_suffix('\\').unwrap_or(relative_path);
+        let names: Vec<&str> = if relative_path.is_empty() {
+            Vec::new()
+        } else {
+            relative_path.split('\\').collect()
+        };
+        if !names.iter().all(|name| is_valid_name(name)) {
+            return Err(invalid_path());
+        }
+
+        let mut item_id_list = vec![
+            RootFolderShellItem::encode(0x50, MY_COMPUTER_CLSID),
+            VolumeShellItem::encode(drive_letter),
+        ];
+        let directory = FileEntryMetadata::new(true);
+        for (index, name) in names.iter().enumerate() {
+            let metadata = if index + 1 == names.len() {
+                metadata
+            } else {
+                &directory
+            };
+            item_id_list.push(FileEntryShellItem::encode(name, metadata));
+        }
+
+        // all names are stored as ASCII or Unicode strings
+        let item_id_list = item_id_list
+            .into_iter()
+            .map(|data| ItemID::from_data(data, encoding_rs::WINDOWS_1252))
+            .collect();
+        Ok(Self { item_id_list })
+    }
+
+    /// returns the size, in bytes, of the binary representation of this
+    /// list, including the terminating TerminalID
+    pub fn size(&self) -> usize {
+        self.item_id_list
+            .iter()
+            .map(|item_id| item_id.data().len() + 2)
+            .sum::<usize>()
+            + 2
+    }
+
+    /// renders the path of the item identified by this list, e.g.
+    /// `C:\Users\x\Documents\a.txt`. Virtual folders are rendered as
+    /// `::{CLSID}`, and items which cannot be decoded as `<unknown 0xNN>`,
+    /// where `NN` is their class type. Returns `None` if the list is empty.
+    pub fn path(&self) -> Option<String> {
+        let mut path = String::new();
+
+        for item_id in self.item_id_list.iter() {
+            let component = match item_id.shell_item() {
+                ShellItem::RootFolder(item) => clsid_path(item.shell_folder_id()),
+                ShellItem::ControlPanel(item) => clsid_path(item.control_panel_id()),
+                ShellItem::FileEntry(item) => {
+                    item.long_name().unwrap_or(item.primary_name()).to_string()
+                }
+
+                // volumes, network locations and URIs are absolute, so they
+                // replace the virtual folders which precede them
+                ShellItem::Volume(item) => {
+                    path.clear();
+                    item.name()
+                        .clone()
+                        .unwrap_or_else(|| unknown_path(item_id.shell_item()))
+                }
+                ShellItem::NetworkLocation(item) => {
+                    path.clear();
+                    item.location().clone()
+                }
+                ShellItem::Uri(item) => {
+                    path.clear();
+                    item.uri().clone()
+                }
+                ShellItem::Raw(_) => unknown_path(item_id.shell_item()),
+            };
+
+            if !path.is_empty() && !path.ends_with('\\') {
+                path.push('\\');
+            }
+            path.push_str(&component);
+        }
+
+        if path.is_empty() {
+            None
+        } else {
+            Some(path)
+        }
+    }
+}
+
+fn clsid_path(clsid: &Guid) -> String {
+    format!("::{{{}}}", clsid.to_string().to_uppercase())
+}
+
+fn unknown_path(item: &ShellItem) -> String {
+    match item.class_type() {
+        Some(class_type) => format!("<unknown 0x{class_type:02x}>"),
+        None => "<unknown>".to_string(),
+    }
+}
+
+/// returns the drive letter of `path` if it is an absolute Windows path, like
+/// `C:\Windows`
+pub(crate) fn windows_drive_letter(path: &str) -> Option<char> {
+    let mut chars = path.chars();
+    match (chars.next(), chars.next(), chars.next()) {
+        (Some(drive), Some(':'), Some('\\')) if drive.is_ascii_alphabetic() => Some(drive),
+        _ => None,
+    }
+}
+
+/// splits the UNC path `path`, like `\\\\server\\share\\dir\\file.exe`, into
+/// the server share (`\\\\server\\share`) and the path relative to the share
+/// (`dir\\file.exe`). Returns `None` if `path` is not a valid UNC path.
+pub(crate) fn split_unc_path(path: &str) -> Option<(&str, &str)> {
+    let rest = path.strip_prefix("\\\\")?;
+    let (server, rest) = rest.split_once('\\')?;
+    let (share, relative_path) = rest.split_once('\\').unwrap_or((rest, ""));
+    let relative_path = relative_path.strip_suffix('\\').unwrap_or(relative_path);
+
+    let is_valid = is_valid_name(server)
+        && is_valid_name(share)
+        && (relative_path.is_empty() || relative_path.split('\\').all(is_valid_name));
+    if !is_valid {
+        return None;
+    }
+    let net_name_length = 2 + server.len() + 1 + share.len();
+    Some((&path[..net_name_length], relative_path))
+}
+
+/// returns `true` if `name` is a valid name of a file or directory
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.encode_utf16().count() <= MAX_NAME_LENGTH
+        && name != "."
+        && name != ".."
+        && !name
+            .chars()
+            .any(|c| c.is_control() || INVALID_NAME_CHARACTERS.contains(c))
+}
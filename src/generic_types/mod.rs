@@ -1,3 +1,4 @@
+pub mod dos_date_time;
 pub mod filetime;
 pub mod guid;
 pub mod idlist;
@@ -2,10 +2,13 @@ use binrw::BinRead;
 use binrw::BinWrite;
 use getset::{Getters, MutGetters, Setters};
 use num_derive::FromPrimitive;
+use num_traits::FromPrimitive as _;
 
 #[cfg(feature = "serde")]
 use serde::Serialize;
 
+use crate::error::FieldError;
+use crate::parse_options::{read_constant, read_reserved, ParseContext};
 use crate::FileTime;
 use crate::Guid;
 
@@ -18,6 +21,9 @@ pub use link_flags::LinkFlags;
 mod file_attributes_flags;
 pub use file_attributes_flags::FileAttributeFlags;
 
+/// the class identifier which every shell link must contain
+const LINK_CLSID: uuid::Uuid = uuid::uuid!("00021401-0000-0000-C000-000000000046");
+
 /// A ShellLinkHeader structure (section 2.1), which contains identification
 /// information, timestamps, and flags that specify the presence of optional
 /// structures.
@@ -25,38 +31,43 @@ pub use file_attributes_flags::FileAttributeFlags;
 #[cfg_attr(feature = "serde", derive(Serialize))]
 #[derive(BinRead)]
 #[cfg_attr(feature = "binwrite", derive(BinWrite))]
-// #[br(little)]
+#[br(import(ctx: &ParseContext))]
 #[getset(get = "pub", get_mut = "pub", set = "pub")]
 pub struct ShellLinkHeader {
     /// The size, in bytes, of this structure. This value MUST be 0x0000004C.
-    #[br(assert(header_size == 0x0000_004c))]
+    #[br(parse_with = read_constant, args("HeaderSize", 0x0000_004c, |v| format!("0x{v:x}")))]
     header_size: u32,
 
     /// This value MUST be 00021401-0000-0000-C000-000000000046.
-    #[br(assert(link_clsid == Guid::from(uuid::uuid!("00021401-0000-0000-C000-000000000046"))))]
+    #[br(parse_with = read_constant, args("LinkCLSID", Guid::from(LINK_CLSID), Guid::to_string))]
     link_clsid: Guid,
 
     /// A LinkFlags structure (section 2.1.1) that specifies information about the shell link and
     /// the presence of optional portions of the structure.
+    #[br(args_raw = Some(ctx))]
     link_flags: LinkFlags,
 
     /// A FileAttributesFlags structure (section 2.1.2) that specifies information about the link
     /// target.
+    #[br(args_raw = Some(ctx))]
     file_attributes: FileAttributeFlags,
 
     /// A FILETIME structure ([MS-DTYP]section 2.3.3) that specifies the creation time of the link
     /// target in UTC (Coordinated Universal Time). If the value is zero, there is no creation time
     /// set on the link target.
+    #[getset(skip)]
     creation_time: FileTime,
 
     /// A FILETIME structure ([MS-DTYP] section2.3.3) that specifies the access time of the link
     /// target in UTC (Coordinated Universal Time). If the value is zero, there is no access time
     /// set on the link target.
+    #[getset(skip)]
     access_time: FileTime,
 
     /// A FILETIME structure ([MS-DTYP] section 2.3.3) that specifies the write time of the link
     /// target in UTC (Coordinated Universal Time). If the value is zero, there is no write time
     /// set on the link target.
+    #[getset(skip)]
     write_time: FileTime,
 
     /// A 32-bit unsigned integer that specifies the size, in bytes, of the link target. If the
@@ -69,25 +80,27 @@ pub struct ShellLinkHeader {
 
     /// A 32-bit unsigned integer that specifies the expected window state of an application
     /// launched by the link.
+    #[br(parse_with = read_show_command, args(ctx))]
     show_command: ShowCommand,
 
     /// A HotkeyFlags structure (section 2.1.3) that specifies the keystrokes used to launch the
     /// application referenced by the shortcut key. This value is assigned to the application after
     /// it is launched, so that pressing the key activates that application.
+    #[br(args(ctx))]
     hotkey: HotkeyFlags,
 
     /// A value that MUST be zero
-    #[br(assert(reserved1 == 0))]
+    #[br(parse_with = read_reserved, args(ctx, "reserved1"))]
     #[cfg_attr(feature = "serde", serde(skip))]
     reserved1: u16,
 
     /// A value that MUST be zero
-    #[br(assert(reserved2 == 0))]
+    #[br(parse_with = read_reserved, args(ctx, "reserved2"))]
     #[cfg_attr(feature = "serde", serde(skip))]
     reserved2: u32,
 
     /// A value that MUST be zero
-    #[br(assert(reserved3 == 0))]
+    #[br(parse_with = read_reserved, args(ctx, "reserved3"))]
     #[cfg_attr(feature = "serde", serde(skip))]
     reserved3: u32,
 }
@@ -97,6 +110,58 @@ impl ShellLinkHeader {
     pub fn update_link_flags(&mut self, link_flags: LinkFlags, value: bool) {
         self.link_flags.set(link_flags, value);
     }
+
+    /// returns the creation time of the link target
+    pub fn creation_time(&self) -> &FileTime {
+        &self.creation_time
+    }
+
+    /// returns a mutable reference to the creation time of the link target
+    pub fn creation_time_mut(&mut self) -> &mut FileTime {
+        &mut self.creation_time
+    }
+
+    /// Set the creation time of the link target, e.g. from a
+    /// [`chrono::DateTime<Utc>`](chrono::DateTime), a
+    /// [`std::time::SystemTime`] or a raw `u64` value
+    pub fn set_creation_time(&mut self, creation_time: impl Into<FileTime>) -> &mut Self {
+        self.creation_time = creation_time.into();
+        self
+    }
+
+    /// returns the access time of the link target
+    pub fn access_time(&self) -> &FileTime {
+        &self.access_time
+    }
+
+    /// returns a mutable reference to the access time of the link target
+    pub fn access_time_mut(&mut self) -> &mut FileTime {
+        &mut self.access_time
+    }
+
+    /// Set the access time of the link target, see
+    /// [`ShellLinkHeader::set_creation_time`]
+    pub fn set_access_time(&mut self, access_time: impl Into<FileTime>) -> &mut Self {
+        self.access_time = access_time.into();
+        self
+    }
+
+    /// returns the write time of the link target
+    pub fn write_time(&self) -> &FileTime {
+        &self.write_time
+    }
+
+    /// returns a mutable reference to the write time of the link target
+    pub fn write_time_mut(&mut self) -> &mut FileTime {
+        &mut self.write_time
+    }
+
+    /// Set the write time of the link target, see
+    /// [`ShellLinkHeader::set_creation_time`]
+    pub fn set_write_time(&mut self, write_time: impl Into<FileTime>) -> &mut Self {
+        self.write_time = write_time.into();
+        self
+    }
 }
 
 impl Default for ShellLinkHeader {
@@ -104,7 +169,7 @@ impl Default for ShellLinkHeader {
     fn default() -> Self {
         Self {
             header_size: 0x4c,
-            link_clsid: Guid::from(uuid::uuid!("00021401-0000-0000-C000-000000000046")),
+            link_clsid: Guid::from(LINK_CLSID),
             link_flags: LinkFlags::IS_UNICODE,
             file_attributes: FileAttributeFlags::FILE_ATTRIBUTE_NORMAL,
             creation_time: FileTime::default(),
@@ -121,6 +186,28 @@ impl Default for ShellLinkHeader {
     }
 }
 
+/// reads the [`ShowCommand`]. According to the specification, all unknown
+/// values must be treated as [`ShowCommand::ShowNormal`], which is what
+/// happens in lenient mode.
+fn read_show_command<R: std::io::Read + std::io::Seek>(
+    reader: &mut R,
+    endian: binrw::Endian,
+    (ctx,): (&ParseContext,),
+) -> binrw::BinResult<ShowCommand> {
+    let pos = reader.stream_position()?;
+    let raw = u32::read_options(reader, endian, ())?;
+    match ShowCommand::from_u32(raw) {
+        Some(show_command) => Ok(show_command),
+        None => {
+            ctx.deviation(
+                pos,
+                FieldError::new("ShowCommand", "a known value", format!("0x{raw:x}")),
+            )?;
+            Ok(ShowCommand::ShowNormal)
+        }
+    }
+}
+
 /// The expected window state of an application launched by the link.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, FromPrimitive, BinRead, BinWrite)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
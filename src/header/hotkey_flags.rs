@@ -5,18 +5,45 @@ use num_derive::FromPrimitive;
 #[cfg(feature = "serde")]
 use serde::Serialize;
 
-use crate::binread_flags::binread_flags;
+use crate::{binread_flags::binread_flags, error::FieldError, parse_options::ParseContext};
 
 /// The HotkeyFlags structure specifies input generated by a combination of keyboard keys being
 /// pressed.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 #[binrw]
 #[cfg_attr(feature = "serde", derive(Serialize))]
+#[br(import(ctx: &ParseContext))]
 pub struct HotkeyFlags {
+    #[br(parse_with = read_hotkey_key, args(ctx))]
     low_byte: HotkeyKey,
+    #[br(args_raw = Some(ctx))]
     high_byte: HotkeyModifiers,
 }
 
+/// reads the [`HotkeyKey`]. Unknown keys are treated as
+/// [`HotkeyKey::NoKeyAssigned`] in lenient mode.
+fn read_hotkey_key<R: std::io::Read + std::io::Seek>(
+    reader: &mut R,
+    endian: binrw::Endian,
+    (ctx,): (&ParseContext,),
+) -> binrw::BinResult<HotkeyKey> {
+    use binrw::BinRead;
+    use num_traits::FromPrimitive;
+
+    let pos = reader.stream_position()?;
+    let raw = u8::read_options(reader, endian, ())?;
+    match HotkeyKey::from_u8(raw) {
+        Some(key) => Ok(key),
+        None => {
+            ctx.deviation(
+                pos,
+                FieldError::new("HotkeyKey", "a known key", format!("0x{raw:x}")),
+            )?;
+            Ok(HotkeyKey::NoKeyAssigned)
+        }
+    }
+}
+
 impl HotkeyFlags {
     /// Create a new HotkeyFlags instance.
     pub fn new(low_byte: HotkeyKey, high_byte: HotkeyModifiers) -> Self {
@@ -1,13 +1,19 @@
 use core::fmt;
 
 use binrw::BinRead;
+#[cfg(feature = "binwrite")]
+use binrw::BinWrite;
+use encoding_rs::Encoding;
 use getset::Getters;
 use serde::Serialize;
 
+use crate::ShellItem;
+
 /// The stored IDList structure specifies the format of a persisted item ID list.
 #[derive(Clone, BinRead, Default, Getters)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
 #[getset(get = "pub")]
+#[br(import(default_codepage: &'static Encoding))]
 pub struct ItemID {
     /// A 16-bit, unsigned integer that specifies the size, in bytes, of the ItemID structure,
     /// including the ItemIDSize field.
@@ -18,10 +24,49 @@ pub struct ItemID {
     /// The shell data source-defined data that specifies an item.
     #[br(if(size > 0), count=if size > 0 {size - 2} else {0})]
     data: Vec<u8>,
+
+    /// The data of this item, decoded according to its class type
+    #[br(calc = ShellItem::parse(&data, default_codepage))]
+    shell_item: ShellItem,
+}
+
+impl ItemID {
+    /// creates an ItemID which contains the shell item data `data`
+    pub(crate) fn from_data(data: Vec<u8>, default_codepage: &'static Encoding) -> Self {
+        Self {
+            size: u16::try_from(data.len() + 2).unwrap_or(u16::MAX),
+            shell_item: ShellItem::parse(&data, default_codepage),
+            data,
+        }
+    }
 }
 
 impl fmt::Debug for ItemID {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "ItemID (raw data size {})", self.size)
+        write!(
+            f,
+            "ItemID (raw data size {}): {:?}",
+            self.size, self.shell_item
+        )
+    }
+}
+
+#[cfg(feature = "binwrite")]
+impl BinWrite for ItemID {
+    type Args<'a> = ();
+
+    fn write_options<W: std::io::Write + std::io::Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        _args: Self::Args<'_>,
+    ) -> binrw::BinResult<()> {
+        let pos = writer.stream_position()?;
+        let size = u16::try_from(self.data.len() + 2).map_err(|_| binrw::Error::AssertFail {
+            pos,
+            message: format!("ItemID data of {} bytes is too large", self.data.len()),
+        })?;
+        size.write_options(writer, endian, ())?;
+        self.data.write_options(writer, endian, ())
     }
 }
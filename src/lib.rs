@@ -34,17 +34,16 @@
 //! > **IMPORTANT!**: Writing capability is currently in a very early stage and probably won't work!
 
 use binrw::BinReaderExt;
-use encoding_rs::Encoding;
+use encoding_rs::{Encoding, WINDOWS_1252};
 use getset::{Getters, MutGetters};
 #[allow(unused)]
 use log::{debug, error, info, trace, warn};
 #[cfg(feature = "serde")]
 use serde::Serialize;
-use thiserror::Error;
 
-use std::io::BufReader;
-#[cfg(feature="binwrite")]
-use std::io::BufWriter;
+use std::io::{BufReader, Cursor, Read, SeekFrom};
+#[cfg(feature = "binwrite")]
+use std::io::{BufWriter, Write};
 use std::path::Path;
 use std::{fs::File, io::Seek};
 
@@ -77,10 +76,36 @@ pub mod extradata;
 pub use extradata::ExtraData;
 
 mod generic_types;
+pub use generic_types::dos_date_time::DosDateTime;
 pub use generic_types::filetime::FileTime;
 pub use generic_types::guid::*;
 pub use generic_types::idlist::*;
 
+mod error;
+pub use error::{BuildError, Error, ParseError, Section};
+
+mod builder;
+pub use builder::{ShellLinkBuilder, TargetMetadata};
+
+mod parse_options;
+pub use parse_options::{
+    CodepageSelection, ParseContext, ParseLimits, ParseOptions, ParseWarning, StructureLocation,
+};
+
+mod codepage;
+pub use codepage::encoding_for_codepage;
+
+mod trailing_data;
+pub use trailing_data::TrailingData;
+
+mod limited_reader;
+use limited_reader::LimitedReader;
+
+#[cfg(feature = "binwrite")]
+mod raw_bytes;
+#[cfg(feature = "binwrite")]
+use raw_bytes::{write_preserving, RawBytes, RawSections};
+
 mod current_offset;
 pub use current_offset::*;
 
@@ -90,22 +115,16 @@ pub use strings::*;
 mod itemid;
 pub use itemid::*;
 
-#[macro_use]
-mod binread_flags;
+/// Decoded representations of the data stored in an [`ItemID`].
+pub mod shell_item;
+pub use shell_item::ShellItem;
 
-/// The error type for shell link parsing errors.
-#[derive(Debug, Error)]
-#[allow(missing_docs)]
-pub enum Error {
-    #[error("An IO error occurred: {0}")]
-    IoError(#[from] std::io::Error),
+/// Decoding of serialized property stores ([MS-PROPSTORE]).
+pub mod property_store;
+pub use property_store::PropertyStore;
 
-    #[error("The parsed file isn't a shell link.")]
-    NotAShellLinkError,
-
-    #[error("Error while parsing: {0}")]
-    BinReadError(#[from] binrw::Error),
-}
+#[macro_use]
+mod binread_flags;
 
 /// A shell link
 #[derive(Debug, Getters, MutGetters)]
@@ -128,6 +147,40 @@ pub struct ShellLink {
     /// returns the [`ExtraData`] structure
     #[allow(unused)]
     extra_data: extradata::ExtraData,
+
+    /// the code page which is used to encode and decode non-Unicode strings
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[getset(skip)]
+    default_codepage: &'static Encoding,
+
+    /// the deviations from the specification which have been found while
+    /// parsing
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    #[getset(skip)]
+    warnings: Vec<ParseWarning>,
+
+    /// the locations of the structures which have been read
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[getset(skip)]
+    locations: Vec<StructureLocation>,
+
+    /// returns the data which follow the ExtraData section, if any
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    #[getset(skip)]
+    trailing_data: Option<TrailingData>,
+
+    /// whether the trailing data are written when the link is saved
+    #[cfg(feature = "binwrite")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[getset(skip)]
+    keep_trailing_data: bool,
+
+    /// the raw bytes of the sections which have been read, which are
+    /// written instead of unmodified sections
+    #[cfg(feature = "binwrite")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[getset(skip)]
+    raw: RawSections,
 }
 
 impl Default for ShellLink {
@@ -141,12 +194,21 @@ impl Default for ShellLink {
             link_info: None,
             string_data: Default::default(),
             extra_data: Default::default(),
+            default_codepage: WINDOWS_1252,
+            warnings: Vec::new(),
+            locations: Vec::new(),
+            trailing_data: None,
+            #[cfg(feature = "binwrite")]
+            keep_trailing_data: false,
+            #[cfg(feature = "binwrite")]
+            raw: RawSections::default(),
         }
     }
 }
 
 impl ShellLink {
     /// Create a new ShellLink pointing to a location, with otherwise default settings.
+    /// On Windows, the link contains a [`LinkTargetIdList`] for the location.
     pub fn new_simple<P: AsRef<Path>>(to: P) -> std::io::Result<Self> {
         use std::fs;
         use std::path::PathBuf;
@@ -163,6 +225,18 @@ impl ShellLink {
 
         let mut sl = Self::default();
 
+        // the IDList can only be created for Windows paths
+        if let Some(path) = canonical.to_str() {
+            let path = if meta.is_dir() {
+                format!("{}\\", path.trim_end_matches('\\'))
+            } else {
+                path.to_string()
+            };
+            if let Ok(id_list) = LinkTargetIdList::from_windows_path(&path) {
+                sl.set_linktarget_id_list(Some(id_list));
+            }
+        }
+
         if meta.is_dir() {
             sl.header_mut()
                 .set_file_attributes(FileAttributeFlags::FILE_ATTRIBUTE_DIRECTORY);
@@ -181,93 +255,87 @@ impl ShellLink {
 
     /// Save a shell link.
     ///
-    /// Note that this doesn't save any [`ExtraData`](struct.ExtraData.html) entries.
-    #[cfg(feature="binwrite")]
-    #[cfg_attr(feature="binwrite", stability::unstable(feature = "binwrite"))]
+    /// All structures whose presence is signaled by the [`LinkFlags`] are
+    /// written, followed by the [`ExtraData`](struct.ExtraData.html) section.
+    /// ANSI strings are encoded using [`ShellLink::default_codepage`].
+    ///
+    /// Structures which have been read from a file and have not been
+    /// modified since then are written exactly as they have been read, so
+    /// that saving an unmodified link reproduces the original file. Data
+    /// which followed the ExtraData section are only written if
+    /// [`ShellLink::set_keep_trailing_data`] has been enabled.
+    #[cfg(feature = "binwrite")]
+    #[cfg_attr(feature = "binwrite", stability::unstable(feature = "binwrite"))]
     pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> Result<(), Error> {
+        let mut w = BufWriter::new(File::create(path)?);
+        self.write_to(&mut w)?;
+        w.flush()?;
+        Ok(())
+    }
+
+    /// Serialize a shell link into a byte vector. See [`ShellLink::save`]
+    /// for details.
+    #[cfg(feature = "binwrite")]
+    #[cfg_attr(feature = "binwrite", stability::unstable(feature = "binwrite"))]
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut cursor = Cursor::new(Vec::new());
+        self.write_to(&mut cursor)?;
+        Ok(cursor.into_inner())
+    }
+
+    /// Write a shell link to any writer. See [`ShellLink::save`] for details.
+    #[cfg(feature = "binwrite")]
+    #[cfg_attr(feature = "binwrite", stability::unstable(feature = "binwrite"))]
+    pub fn write_to<W: Write + Seek>(&self, mut writer: W) -> Result<(), Error> {
         use binrw::BinWrite;
 
-        let mut w = BufWriter::new(File::create(path)?);
+        let w = &mut writer;
+        let endian = binrw::Endian::Little;
 
         debug!("Writing header...");
-        // Invoke binwrite
-        self.header()
-            .write_options(&mut w, binrw::Endian::Little, ())?;
-
-        // let link_flags = *self.header().link_flags();
-
-        // if link_flags.contains(LinkFlags::HAS_LINK_TARGET_ID_LIST) {
-        //     if let None = self.linktarget_id_list {
-        //         error!("LinkTargetIDList not specified but expected!")
-        //     }
-        //     debug!("A LinkTargetIDList is marked as present. Writing.");
-        //     let mut data: Vec<u8> = self.linktarget_id_list.clone().unwrap().into();
-        //     w.write_all(&mut data)?;
-        // }
-
-        // if link_flags.contains(LinkFlags::HAS_LINK_INFO) {
-        //     if let None = self.link_info {
-        //         error!("LinkInfo not specified but expected!")
-        //     }
-        //     debug!("LinkInfo is marked as present. Writing.");
-        //     let mut data: Vec<u8> = self.link_info.clone().unwrap().into();
-        //     w.write_all(&mut data)?;
-        // }
-
-        // if link_flags.contains(LinkFlags::HAS_NAME) {
-        //     if self.name_string == None {
-        //         error!("Name not specified but expected!")
-        //     }
-        //     debug!("Name is marked as present. Writing.");
-        //     w.write_all(&stringdata::to_data(
-        //         self.name_string.as_ref().unwrap(),
-        //         link_flags,
-        //     ))?;
-        // }
-
-        // if link_flags.contains(LinkFlags::HAS_RELATIVE_PATH) {
-        //     if self.relative_path == None {
-        //         error!("Relative path not specified but expected!")
-        //     }
-        //     debug!("Relative path is marked as present. Writing.");
-        //     w.write_all(&stringdata::to_data(
-        //         self.relative_path.as_ref().unwrap(),
-        //         link_flags,
-        //     ))?;
-        // }
-
-        // if link_flags.contains(LinkFlags::HAS_WORKING_DIR) {
-        //     if self.working_dir == None {
-        //         error!("Working Directory not specified but expected!")
-        //     }
-        //     debug!("Working dir is marked as present. Writing.");
-        //     w.write_all(&stringdata::to_data(
-        //         self.working_dir.as_ref().unwrap(),
-        //         link_flags,
-        //     ))?;
-        // }
-
-        // if link_flags.contains(LinkFlags::HAS_ARGUMENTS) {
-        //     if self.icon_location == None {
-        //         error!("Arguments not specified but expected!")
-        //     }
-        //     debug!("Arguments are marked as present. Writing.");
-        //     w.write_all(&stringdata::to_data(
-        //         self.command_line_arguments.as_ref().unwrap(),
-        //         link_flags,
-        //     ))?;
-        // }
-
-        // if link_flags.contains(LinkFlags::HAS_ICON_LOCATION) {
-        //     if self.icon_location == None {
-        //         error!("Icon Location not specified but expected!")
-        //     }
-        //     debug!("Icon Location is marked as present. Writing.");
-        //     w.write_all(&stringdata::to_data(
-        //         self.icon_location.as_ref().unwrap(),
-        //         link_flags,
-        //     ))?;
-        // }
+        write_preserving(w, self.header(), (), self.raw.header.as_ref())?;
+
+        let link_flags = *self.header().link_flags();
+
+        if link_flags.contains(LinkFlags::HAS_LINK_TARGET_ID_LIST) {
+            debug!("A LinkTargetIDList is marked as present. Writing.");
+            let id_list = self
+                .linktarget_id_list
+                .as_ref()
+                .ok_or(Error::MissingStructure("LinkTargetIDList"))?;
+            write_preserving(w, id_list, (), self.raw.linktarget_id_list.as_ref())?;
+        }
+
+        if link_flags.contains(LinkFlags::HAS_LINK_INFO) {
+            debug!("LinkInfo is marked as present. Writing.");
+            let link_info = self
+                .link_info
+                .as_ref()
+                .ok_or(Error::MissingStructure("LinkInfo"))?;
+            write_preserving(
+                w,
+                link_info,
+                (self.default_codepage,),
+                self.raw.link_info.as_ref(),
+            )?;
+        }
+
+        debug!("Writing StringData...");
+        write_preserving(
+            w,
+            &self.string_data,
+            (link_flags, self.default_codepage),
+            self.raw.string_data.as_ref(),
+        )?;
+
+        debug!("Writing ExtraData...");
+        self.extra_data
+            .write_options(w, endian, (self.default_codepage,))?;
+        if self.keep_trailing_data {
+            if let Some(trailing_data) = &self.trailing_data {
+                w.write_all(trailing_data.data())?;
+            }
+        }
 
         Ok(())
     }
@@ -278,37 +346,227 @@ impl ShellLink {
         default_codepage: &'static Encoding,
     ) -> Result<Self, Error> {
         debug!("Opening {:?}", path.as_ref());
-        let mut reader = BufReader::new(File::open(path)?);
+        let reader = BufReader::new(File::open(path)?);
         trace!("Reading file.");
+        Self::from_reader(reader, default_codepage)
+    }
+
+    /// Open and parse a shell link, using the given [`ParseOptions`]. In
+    /// lenient mode, the deviations from the specification which have been
+    /// found are available via [`ShellLink::warnings`].
+    pub fn open_with_options<P: AsRef<std::path::Path>>(
+        path: P,
+        default_codepage: &'static Encoding,
+        options: ParseOptions,
+    ) -> Result<Self, Error> {
+        debug!("Opening {:?}", path.as_ref());
+        let reader = BufReader::new(File::open(path)?);
+        Self::from_reader_with_options(reader, default_codepage, options)
+    }
 
-        let shell_link_header: ShellLinkHeader = reader.read_le()?;
+    /// Parse a shell link which is stored in a byte slice
+    pub fn from_bytes(data: &[u8], default_codepage: &'static Encoding) -> Result<Self, Error> {
+        Self::from_reader(Cursor::new(data), default_codepage)
+    }
+
+    /// Parse a shell link which is stored in a byte slice, using the given
+    /// [`ParseOptions`]
+    pub fn from_bytes_with_options(
+        data: &[u8],
+        default_codepage: &'static Encoding,
+        options: ParseOptions,
+    ) -> Result<Self, Error> {
+        Self::from_reader_with_options(Cursor::new(data), default_codepage, options)
+    }
+
+    /// Parse a shell link from any reader. Parsing starts at the current
+    /// position of the reader.
+    pub fn from_reader<R: Read + Seek>(
+        reader: R,
+        default_codepage: &'static Encoding,
+    ) -> Result<Self, Error> {
+        Self::from_reader_with_options(reader, default_codepage, ParseOptions::default())
+    }
+
+    /// Parse a shell link from any reader, using the given [`ParseOptions`].
+    /// Parsing starts at the current position of the reader.
+    ///
+    /// In lenient mode, structures which cannot be parsed are skipped, if
+    /// their size is known, and a [`ParseWarning`] is recorded for each of
+    /// them. Only an invalid [`ShellLinkHeader`] is always fatal.
+    ///
+    /// Exceeding the [`ParseLimits`] is an error in lenient mode, too.
+    ///
+    /// Strings which are not stored as Unicode are decoded using
+    /// `default_codepage`, unless another code page is selected according to
+    /// [`ParseOptions::codepage_selection`].
+    pub fn from_reader_with_options<R: Read + Seek>(
+        mut reader: R,
+        default_codepage: &'static Encoding,
+        options: ParseOptions,
+    ) -> Result<Self, Error> {
+        let start = reader.stream_position()?;
+        let link = Self::read_with_codepage(&mut reader, default_codepage, options.clone())?;
+
+        let codepage = match options.codepage_selection {
+            CodepageSelection::Fixed => None,
+            CodepageSelection::FromLink => codepage::codepage_from_link(&link),
+            CodepageSelection::Guess => {
+                codepage::codepage_from_link(&link).or_else(|| codepage::guess_codepage(&link))
+            }
+        };
+        match codepage {
+            Some(codepage) if codepage != default_codepage => {
+                debug!("parsing the link again using {}", codepage.name());
+                reader.seek(SeekFrom::Start(start))?;
+                match Self::read_with_codepage(&mut reader, codepage, options) {
+                    Ok(link) => Ok(link),
+                    Err(why) => {
+                        warn!("unable to parse the link using {}: {why}", codepage.name());
+                        Ok(link)
+                    }
+                }
+            }
+            _ => Ok(link),
+        }
+    }
+
+    /// parses a shell link, decoding strings which are not stored as Unicode
+    /// using `default_codepage`
+    fn read_with_codepage<R: Read + Seek>(
+        reader: R,
+        default_codepage: &'static Encoding,
+        options: ParseOptions,
+    ) -> Result<Self, Error> {
+        let mut reader = LimitedReader::new(reader, options.limits.max_size)?;
+        let ctx = ParseContext::new(options);
+        #[cfg(feature = "binwrite")]
+        let mut raw = RawSections::default();
+        let start = reader.stream_position()?;
+
+        let shell_link_header: ShellLinkHeader = reader.read_le_args((&ctx,)).map_err(|why| {
+            let error = ParseError::from_binrw(Section::Header, &why, start);
+            match error.field().as_deref() {
+                Some("HeaderSize" | "LinkCLSID") => Error::NotAShellLinkError,
+                _ => error.into(),
+            }
+        })?;
         debug!("Shell header: {:#?}", shell_link_header);
+        ctx.record_location("ShellLinkHeader", start, reader.stream_position()?);
+        #[cfg(feature = "binwrite")]
+        {
+            raw.header = RawBytes::capture(&mut reader, start, &shell_link_header, ())?;
+        }
 
         let mut linktarget_id_list = None;
         let link_flags = *shell_link_header.link_flags();
         if link_flags.contains(LinkFlags::HAS_LINK_TARGET_ID_LIST) {
-            debug!(
-                "A LinkTargetIDList is marked as present. Parsing now at position 0x{:0x}",
-                reader.stream_position()?
-            );
-            let list: LinkTargetIdList = reader.read_le()?;
-            debug!("{:?}", list);
-            linktarget_id_list = Some(list);
+            let start = reader.stream_position()?;
+            debug!("A LinkTargetIDList is marked as present. Parsing now at position 0x{start:0x}");
+            match reader.read_le_args::<LinkTargetIdList>((default_codepage, &ctx)) {
+                Ok(list) => {
+                    debug!("{:?}", list);
+                    ctx.record_location("LinkTargetIDList", start, reader.stream_position()?);
+                    list.record_locations(start, &ctx);
+                    #[cfg(feature = "binwrite")]
+                    {
+                        raw.linktarget_id_list = RawBytes::capture(&mut reader, start, &list, ())?;
+                    }
+                    linktarget_id_list = Some(list);
+                }
+                Err(why) if ctx.can_recover() => {
+                    let error = ParseError::from_binrw(Section::LinkTargetIdList, &why, start);
+                    ctx.warn(start, format!("unable to read {error}"));
+                    ctx.discard_locations(start);
+                    reader.seek(SeekFrom::Start(start))?;
+                    let size: u16 = reader.read_le()?;
+                    reader.seek(SeekFrom::Start(start + 2 + u64::from(size)))?;
+                }
+                Err(why) => {
+                    return Err(
+                        ParseError::from_binrw(Section::LinkTargetIdList, &why, start).into(),
+                    )
+                }
+            }
         }
 
         let mut link_info = None;
         if link_flags.contains(LinkFlags::HAS_LINK_INFO) {
-            debug!(
-                "LinkInfo is marked as present. Parsing now at position 0x{:0x}",
-                reader.stream_position()?
-            );
-            let info: LinkInfo = reader.read_le_args((default_codepage,))?;
-            debug!("{:?}", info);
-            link_info = Some(info);
+            let start = reader.stream_position()?;
+            debug!("LinkInfo is marked as present. Parsing now at position 0x{start:0x}");
+            match reader.read_le_args::<LinkInfo>((default_codepage, &ctx)) {
+                Ok(info) => {
+                    debug!("{:?}", info);
+                    ctx.record_location("LinkInfo", start, reader.stream_position()?);
+                    info.record_locations(&ctx);
+                    #[cfg(feature = "binwrite")]
+                    {
+                        raw.link_info =
+                            RawBytes::capture(&mut reader, start, &info, (default_codepage,))?;
+                    }
+                    link_info = Some(info);
+                }
+                Err(why) if ctx.can_recover() => {
+                    let error = ParseError::from_binrw(Section::LinkInfo, &why, start);
+                    ctx.warn(start, format!("unable to read {error}"));
+                    ctx.discard_locations(start);
+                    reader.seek(SeekFrom::Start(start))?;
+                    let size: u32 = reader.read_le()?;
+                    reader.seek(SeekFrom::Start(start + u64::from(size)))?;
+                }
+                Err(why) => {
+                    return Err(ParseError::from_binrw(Section::LinkInfo, &why, start).into())
+                }
+            }
         }
 
-        let string_data: StringData = reader.read_le_args((link_flags, default_codepage))?;
-        let extra_data: ExtraData = reader.read_le_args((default_codepage,))?;
+        let start = reader.stream_position()?;
+        let mut trailing_data = None;
+        let (string_data, extra_data) =
+            match reader.read_le_args::<StringData>((link_flags, default_codepage, &ctx)) {
+                Ok(string_data) => {
+                    ctx.record_location("StringData", start, reader.stream_position()?);
+                    #[cfg(feature = "binwrite")]
+                    {
+                        raw.string_data = RawBytes::capture(
+                            &mut reader,
+                            start,
+                            &string_data,
+                            (link_flags, default_codepage),
+                        )?;
+                    }
+                    let start = reader.stream_position()?;
+                    let extra_data: ExtraData = reader
+                        .read_le_args((default_codepage, &ctx))
+                        .map_err(|why| ParseError::from_binrw(Section::ExtraData, &why, start))?;
+                    ctx.record_location("ExtraData", start, reader.stream_position()?);
+
+                    // the link might be part of a larger stream, so the
+                    // trailing data end at the maximum size of the link
+                    let start = reader.stream_position()?;
+                    let remaining = reader.remaining();
+                    let mut data = Vec::new();
+                    reader.by_ref().take(remaining).read_to_end(&mut data)?;
+                    if !data.is_empty() {
+                        warn!("found {} bytes after the ExtraData section", data.len());
+                        ctx.record_location("TrailingData", start, reader.stream_position()?);
+                        trailing_data = Some(TrailingData::new(start, data));
+                    }
+                    (string_data, extra_data)
+                }
+                Err(why) if ctx.can_recover() => {
+                    // the size of the StringData is unknown, so we cannot
+                    // find the ExtraData section
+                    let error = ParseError::from_binrw(Section::StringData, &why, start);
+                    ctx.warn(start, format!("unable to read {error}"));
+                    ctx.discard_locations(start);
+                    (StringData::default(), ExtraData::default())
+                }
+                Err(why) => {
+                    return Err(ParseError::from_binrw(Section::StringData, &why, start).into())
+                }
+            };
+        reader.check()?;
 
         Ok(Self {
             header: shell_link_header,
@@ -316,44 +574,181 @@ impl ShellLink {
             link_info,
             string_data,
             extra_data,
+            default_codepage,
+            locations: ctx.take_locations(),
+            trailing_data,
+            #[cfg(feature = "binwrite")]
+            keep_trailing_data: false,
+            warnings: ctx.into_warnings(),
+            #[cfg(feature = "binwrite")]
+            raw,
         })
     }
 
+    /// returns the deviations from the specification which have been found
+    /// while parsing this shell link in lenient mode, see [`ParseOptions`]
+    pub fn warnings(&self) -> &[ParseWarning] {
+        &self.warnings
+    }
+
+    /// returns the locations of the structures which have been read from a
+    /// file, ordered by their offset, e.g. the location of the header, of each
+    /// `ItemID`, of each string and of each ExtraData block. The locations
+    /// describe the file as it has been read and are not updated if the
+    /// shell link is modified.
+    pub fn locations(&self) -> &[StructureLocation] {
+        &self.locations
+    }
+
+    /// returns the location of the structure identified by `path`, e.g.
+    /// `LinkInfo/VolumeID`, see [`StructureLocation::path`]
+    pub fn location(&self, path: &str) -> Option<&StructureLocation> {
+        self.locations
+            .iter()
+            .find(|location| location.path() == path)
+    }
+
+    /// returns the data which follow the ExtraData section. Such data are
+    /// ignored by Windows, so they might be a hidden payload. They end at
+    /// the end of the stream or at [`ParseLimits::max_size`], whichever
+    /// comes first.
+    pub fn trailing_data(&self) -> Option<&TrailingData> {
+        self.trailing_data.as_ref()
+    }
+
+    /// returns whether the [`ShellLink::trailing_data`] are written when the
+    /// link is saved. This is `false` by default, so that saving a link
+    /// strips any data which have been appended to it.
+    #[cfg(feature = "binwrite")]
+    #[cfg_attr(feature = "binwrite", stability::unstable(feature = "binwrite"))]
+    pub fn keep_trailing_data(&self) -> bool {
+        self.keep_trailing_data
+    }
+
+    /// sets whether the [`ShellLink::trailing_data`] are written when the
+    /// link is saved, e.g. to reproduce the original file byte by byte
+    #[cfg(feature = "binwrite")]
+    #[cfg_attr(feature = "binwrite", stability::unstable(feature = "binwrite"))]
+    pub fn set_keep_trailing_data(&mut self, keep_trailing_data: bool) {
+        self.keep_trailing_data = keep_trailing_data;
+    }
+
+    /// returns the code page which is used to encode and decode strings
+    /// which are not stored as Unicode. For links which have been read from
+    /// a file, this is the code page that has been selected while parsing,
+    /// see [`CodepageSelection`].
+    pub fn default_codepage(&self) -> &'static Encoding {
+        self.default_codepage
+    }
+
+    /// sets the code page which is used to encode strings which are not
+    /// stored as Unicode
+    pub fn set_default_codepage(&mut self, default_codepage: &'static Encoding) {
+        self.default_codepage = default_codepage;
+    }
+
     /// returns the full path of the link target. This information
     /// is constructed completely from the LINK_INFO structure. So,
     /// if the lnk file does not contain such a structure, the result
-    /// of this method will be `None`
+    /// of this method will be `None`; use [`ShellLink::id_list_target`]
+    /// in that case. `None` is also returned if the LINK_INFO structure
+    /// lacks the base path which is required by its flags.
+    ///
+    /// The base path and the CommonPathSuffix are joined with a backslash.
+    /// If the CommonPathSuffix is empty, which is how Windows stores local
+    /// targets, the base path is returned as is, e.g. `C:\test\a.txt` and
+    /// not `C:\test\a.txt\`.
     pub fn link_target(&self) -> Option<String> {
-        if let Some(info) = self.link_info().as_ref() {
-            let base_path = if info
-                .link_info_flags()
-                .has_common_network_relative_link_and_path_suffix()
-            {
-                info.common_network_relative_link()
-                    .as_ref()
-                    .expect("missing common network relative link")
-                    .name()
-            } else {
-                info.local_base_path_unicode()
-                    .as_ref()
-                    .map(|s| &s[..])
-                    .or(info.local_base_path())
-                    .expect("missing local base path")
-                    .to_string()
-            };
+        let info = self.link_info().as_ref()?;
+        let base_path = if info
+            .link_info_flags()
+            .has_common_network_relative_link_and_path_suffix()
+        {
+            info.common_network_relative_link().as_ref()?.name()?
+        } else {
+            info.local_base_path_unicode()
+                .as_deref()
+                .or(info.local_base_path())?
+                .to_string()
+        };
+
+        let common_path = info
+            .common_path_suffix_unicode()
+            .as_deref()
+            .unwrap_or(info.common_path_suffix());
+
+        let separator = if common_path.is_empty() || base_path.ends_with('\\') {
+            ""
+        } else {
+            "\\"
+        };
 
-            let separator = if base_path.ends_with('\\') { "" } else { "\\" };
+        Some(format!("{base_path}{separator}{common_path}"))
+    }
 
-            let common_path = info
-                .common_path_suffix_unicode()
-                .as_ref()
-                .map(|s| &s[..])
-                .unwrap_or(info.common_path_suffix());
+    /// returns the full path of the link target, as it is rendered from
+    /// the LinkTargetIDList. If the lnk file does not contain such a
+    /// structure, the IDList of the VistaAndAboveIDListDataBlock is used
+    /// instead. Virtual folders are rendered as `::{CLSID}`. See
+    /// [`IdList::path`].
+    pub fn id_list_target(&self) -> Option<String> {
+        self.linktarget_id_list()
+            .as_ref()
+            .and_then(LinkTargetIdList::path)
+            .or_else(|| {
+                self.extra_data
+                    .vista_and_above_id_list()
+                    .and_then(|block| block.id_list().path())
+            })
+    }
 
-            Some(format!("{base_path}{separator}{common_path}"))
-        } else {
-            None
+    /// Insert a block into the extra data section of the shell link. If
+    /// there already is a block with the same signature, it is replaced and
+    /// returned. The corresponding [`LinkFlags`] bit, like
+    /// [`LinkFlags::HAS_EXP_STRING`], is set.
+    pub fn insert_extra_data_block(
+        &mut self,
+        block: extradata::ExtraDataBlock,
+    ) -> Option<extradata::ExtraDataBlock> {
+        if let Some(flag) = block.link_flag() {
+            self.header_mut().update_link_flags(flag, true);
+        }
+        self.extra_data.insert(block)
+    }
+
+    /// Remove the block which is identified by `signature` from the extra
+    /// data section of the shell link, and return it. The corresponding
+    /// [`LinkFlags`] bit, like [`LinkFlags::HAS_EXP_STRING`], is cleared.
+    pub fn remove_extra_data_block(&mut self, signature: u32) -> Option<extradata::ExtraDataBlock> {
+        let block = self.extra_data.remove(signature)?;
+        if let Some(flag) = block.link_flag() {
+            self.header_mut().update_link_flags(flag, false);
+        }
+        Some(block)
+    }
+
+    /// Set the shell link's [`LinkTargetIdList`]. The
+    /// [`LinkFlags::HAS_LINK_TARGET_ID_LIST`] flag is updated accordingly.
+    pub fn set_linktarget_id_list(&mut self, linktarget_id_list: Option<LinkTargetIdList>) {
+        self.header_mut().update_link_flags(
+            LinkFlags::HAS_LINK_TARGET_ID_LIST,
+            linktarget_id_list.is_some(),
+        );
+        self.linktarget_id_list = linktarget_id_list;
+    }
+
+    /// Set the shell link's [`LinkInfo`]. The [`LinkFlags::HAS_LINK_INFO`]
+    /// flag is updated accordingly, and [`LinkFlags::FORCE_NO_LINK_INFO`] is
+    /// cleared if there is a LinkInfo.
+    pub fn set_link_info(&mut self, link_info: Option<LinkInfo>) {
+        let has_link_info = link_info.is_some();
+        self.header_mut()
+            .update_link_flags(LinkFlags::HAS_LINK_INFO, has_link_info);
+        if has_link_info {
+            self.header_mut()
+                .update_link_flags(LinkFlags::FORCE_NO_LINK_INFO, false);
         }
+        self.link_info = link_info;
     }
 
     /// Set the shell link's name
@@ -0,0 +1,81 @@
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// wraps a reader and refuses to read any data beyond `limit` bytes after
+/// the position where reading started
+pub(crate) struct LimitedReader<R> {
+    inner: R,
+    pos: u64,
+    end: u64,
+    limit: u64,
+    exceeded: bool,
+}
+
+impl<R: Seek> LimitedReader<R> {
+    pub(crate) fn new(mut inner: R, limit: u64) -> io::Result<Self> {
+        let pos = inner.stream_position()?;
+        Ok(Self {
+            inner,
+            pos,
+            end: pos.saturating_add(limit),
+            limit,
+            exceeded: false,
+        })
+    }
+}
+
+impl<R> LimitedReader<R> {
+    /// returns the number of bytes which can be read before the limit is
+    /// reached
+    pub(crate) fn remaining(&self) -> u64 {
+        self.end.saturating_sub(self.pos)
+    }
+
+    /// returns an error if an attempt to read beyond the limit has been
+    /// made, even if the error has been handled by a lenient parser
+    pub(crate) fn check(&self) -> io::Result<()> {
+        if self.exceeded {
+            Err(self.error())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn error(&self) -> io::Error {
+        io::Error::other(format!(
+            "the shell link exceeds the maximum size of {} bytes",
+            self.limit
+        ))
+    }
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let remaining = self.end.saturating_sub(self.pos);
+        if remaining == 0 {
+            // reaching the limit is fine, but exceeding it is not
+            if self.inner.read(&mut [0u8])? == 0 {
+                return Ok(0);
+            }
+            self.exceeded = true;
+            return Err(self.error());
+        }
+
+        let len = buf
+            .len()
+            .min(usize::try_from(remaining).unwrap_or(usize::MAX));
+        let count = self.inner.read(&mut buf[..len])?;
+        self.pos += count as u64;
+        Ok(count)
+    }
+}
+
+impl<R: Seek> Seek for LimitedReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.pos = self.inner.seek(pos)?;
+        Ok(self.pos)
+    }
+}
@@ -1,15 +1,17 @@
-use core::panic;
-
-use binrw::BinRead;
+use binrw::{BinRead, BinWrite};
 use bitflags::bitflags;
 use encoding_rs::Encoding;
 use getset::Getters;
 use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::FromPrimitive as _;
+use std::io::SeekFrom;
 
 use crate::{
     binread_flags::binread_flags,
+    error::FieldError,
+    parse_options::ParseContext,
     strings::{NullTerminatedString, StringEncoding},
-    CurrentOffset,
+    CurrentOffset, Located,
 };
 
 #[cfg(feature = "serde")]
@@ -25,8 +27,12 @@ use serde::Serialize;
 #[cfg_attr(feature = "serde", derive(Serialize))]
 #[getset(get = "pub")]
 #[allow(unused)]
-#[br(import(default_codepage: &'static Encoding))]
+#[br(import(default_codepage: &'static Encoding, ctx: &ParseContext))]
 pub struct LinkInfo {
+    #[getset(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    start_offset: CurrentOffset,
+
     /// LinkInfoSize (4 bytes): A 32-bit, unsigned integer that specifies the
     /// size, in bytes, of the LinkInfo structure. All offsets specified in
     /// this structure MUST be less than this value, and all strings contained
@@ -44,6 +50,7 @@ pub struct LinkInfo {
     /// Flags that specify whether the VolumeID, LocalBasePath,
     /// LocalBasePathUnicode, and CommonNetworkRelativeLinkfields are present
     /// in this structure.
+    #[br(args_raw = Some(ctx))]
     link_info_flags: LinkInfoFlags,
 
     /// VolumeIDOffset (4 bytes): A 32-bit, unsigned integer that specifies the
@@ -51,13 +58,19 @@ pub struct LinkInfo {
     /// set, this value is an offset, in bytes, from the start of the LinkInfo
     /// structure; otherwise, this value MUST be zero.
     #[br(
-        assert(
+        assert(ctx.check(
             if link_info_flags.has_volume_id_and_local_base_path() {
                 volume_id_offset > 0 && volume_id_offset < link_info_size
             } else {
                 volume_id_offset == 0
-            }
-        )
+            },
+            start_offset.at(0x0c),
+            || FieldError::new(
+                "VolumeIDOffset",
+                expected_offset(link_info_flags.has_volume_id_and_local_base_path(), link_info_size),
+                format!("0x{volume_id_offset:x}")
+            )
+        )?)
     )]
     volume_id_offset: u32,
 
@@ -67,13 +80,19 @@ pub struct LinkInfo {
     /// bytes, from the start of the LinkInfo structure; otherwise, this value
     /// MUST be zero.
     #[br(
-        assert(
+        assert(ctx.check(
             if link_info_flags.has_volume_id_and_local_base_path() {
                 local_base_path_offset > 0 && local_base_path_offset < link_info_size
             } else {
                 local_base_path_offset == 0
-            }
-        )
+            },
+            start_offset.at(0x10),
+            || FieldError::new(
+                "LocalBasePathOffset",
+                expected_offset(link_info_flags.has_volume_id_and_local_base_path(), link_info_size),
+                format!("0x{local_base_path_offset:x}")
+            )
+        )?)
     )]
     local_base_path_offset: u32,
 
@@ -83,20 +102,36 @@ pub struct LinkInfo {
     /// an offset, in bytes, from the start of the LinkInfo structure;
     /// otherwise, this value MUST be zero.
     #[br(
-        assert(
+        assert(ctx.check(
             if link_info_flags.has_common_network_relative_link_and_path_suffix() {
                 common_network_relative_link_offset > 0 && common_network_relative_link_offset < link_info_size
             } else {
                 common_network_relative_link_offset == 0
-            }
-        )
+            },
+            start_offset.at(0x14),
+            || FieldError::new(
+                "CommonNetworkRelativeLinkOffset",
+                expected_offset(link_info_flags.has_common_network_relative_link_and_path_suffix(), link_info_size),
+                format!("0x{common_network_relative_link_offset:x}")
+            )
+        )?)
     )]
     common_network_relative_link_offset: u32,
 
     /// CommonPathSuffixOffset (4 bytes): A 32-bit, unsigned integer that
     /// specifies the location of the CommonPathSuffix field. This value is
     /// an offset, in bytes, from the start of the LinkInfo structure.
-    #[br(assert(common_path_suffix_offset < link_info_size && common_path_suffix_offset != 0))]
+    #[br(
+        assert(ctx.check(
+            common_path_suffix_offset < link_info_size && common_path_suffix_offset != 0,
+            start_offset.at(0x18),
+            || FieldError::new(
+                "CommonPathSuffixOffset",
+                expected_offset(true, link_info_size),
+                format!("0x{common_path_suffix_offset:x}")
+            )
+        )?)
+    )]
     common_path_suffix_offset: u32,
 
     /// LocalBasePathOffsetUnicode (4 bytes): An optional, 32-bit, unsigned
@@ -107,17 +142,23 @@ pub struct LinkInfo {
     /// LinkInfoHeaderSize field is greater than or equal to 0x00000024.
     #[br(
         if(link_info_header_size >= 0x24),
-        assert(
+        assert(ctx.check(
             if let Some(offset) = local_base_path_offset_unicode {
                 if link_info_flags.has_volume_id_and_local_base_path(){
                     offset > 0 && offset < link_info_size
                 } else {
-                    false
+                    offset == 0
                 }
             } else {
                 true
-            }
-        )
+            },
+            start_offset.at(0x1c),
+            || FieldError::new(
+                "LocalBasePathOffsetUnicode",
+                expected_offset(link_info_flags.has_volume_id_and_local_base_path(), link_info_size),
+                format!("0x{:x}", local_base_path_offset_unicode.unwrap_or_default())
+            )
+        )?)
     )]
     local_base_path_offset_unicode: Option<u32>,
 
@@ -128,15 +169,17 @@ pub struct LinkInfo {
     /// LinkInfoHeaderSize field is greater than or equal to 0x00000024.
     #[br(
         if(link_info_header_size >= 0x24),
-        assert (
+        assert(ctx.check(
             if let Some(offset) = common_path_suffix_offset_unicode {
-                if link_info_flags.has_common_network_relative_link_and_path_suffix() {
-                    offset > 0 && offset < link_info_size
-                } else {
-                    false
-                }
-            } else {true}
-        )
+                offset < link_info_size
+            } else {true},
+            start_offset.at(0x20),
+            || FieldError::new(
+                "CommonPathSuffixOffsetUnicode",
+                format!("an offset below 0x{link_info_size:x}"),
+                format!("0x{:x}", common_path_suffix_offset_unicode.unwrap_or_default())
+            )
+        )?)
     )]
     common_path_suffix_offset_unicode: Option<u32>,
 
@@ -145,9 +188,9 @@ pub struct LinkInfo {
     /// was created. This field is present if the VolumeIDAndLocalBasePath
     /// flag is set.
     #[br(
-        //seek_before(SeekFrom::Start((*start_offset.as_ref() + volume_id_offset).into())),
         if(link_info_flags.has_volume_id_and_local_base_path()),
-        args(default_codepage)
+        seek_before = SeekFrom::Start(start_offset.at(volume_id_offset.into())),
+        args(default_codepage, ctx)
     )]
     volume_id: Option<VolumeID>,
 
@@ -157,8 +200,9 @@ pub struct LinkInfo {
     /// field is present if the VolumeIDAndLocalBasePath flag is set.
     #[br(
         if(link_info_flags.has_volume_id_and_local_base_path()),
+        seek_before = SeekFrom::Start(start_offset.at(local_base_path_offset.into())),
         args(StringEncoding::CodePage(default_codepage)),
-        map=|o: Option<NullTerminatedString>| o.map(|n| n.to_string())
+        map=|o: Option<Located<NullTerminatedString>>| o.map(|n| n.record(ctx, "LinkInfo/LocalBasePath").to_string())
     )]
     #[getset(skip)]
     local_base_path: Option<String>,
@@ -168,7 +212,8 @@ pub struct LinkInfo {
     /// is stored.
     #[br(
         if(link_info_flags.has_common_network_relative_link_and_path_suffix()),
-        args(default_codepage)
+        seek_before = SeekFrom::Start(start_offset.at(common_network_relative_link_offset.into())),
+        args(default_codepage, ctx)
     )]
     common_network_relative_link: Option<CommonNetworkRelativeLink>,
 
@@ -177,8 +222,9 @@ pub struct LinkInfo {
     /// target by being appended to the string in the LocalBasePath field.
     #[br(
         if(common_path_suffix_offset != 0),
+        seek_before = SeekFrom::Start(start_offset.at(common_path_suffix_offset.into())),
         args(StringEncoding::CodePage(default_codepage)),
-        map=|n: NullTerminatedString| n.to_string()
+        map=|n: Located<NullTerminatedString>| n.record(ctx, "LinkInfo/CommonPathSuffix").to_string()
     )]
     #[getset(skip)]
     common_path_suffix: String,
@@ -190,8 +236,9 @@ pub struct LinkInfo {
     /// LinkInfoHeaderSize field is greater than or equal to 0x00000024.
     #[br(
         if(link_info_header_size >= 0x24 && link_info_flags.has_volume_id_and_local_base_path()),
+        seek_before = SeekFrom::Start(start_offset.at(local_base_path_offset_unicode.unwrap_or_default().into())),
         args(StringEncoding::Unicode),
-        map=|o: Option<NullTerminatedString>| o.map(|n| n.to_string())
+        map=|o: Option<Located<NullTerminatedString>>| o.map(|n| n.record(ctx, "LinkInfo/LocalBasePathUnicode").to_string())
     )]
     local_base_path_unicode: Option<String>,
 
@@ -202,13 +249,92 @@ pub struct LinkInfo {
     /// equal to 0x00000024.
     #[br(
         if(link_info_header_size >= 0x24 && common_path_suffix_offset_unicode.map(|o| o != 0).unwrap_or(false)),
+        seek_before = SeekFrom::Start(start_offset.at(common_path_suffix_offset_unicode.unwrap_or_default().into())),
         args(StringEncoding::Unicode),
-        map=|o: Option<NullTerminatedString>| o.map(|n| n.to_string())
+        map=|o: Option<Located<NullTerminatedString>>| o.map(|n| n.record(ctx, "LinkInfo/CommonPathSuffixUnicode").to_string())
     )]
     common_path_suffix_unicode: Option<String>,
+
+    /// the end of the structure, as specified by LinkInfoSize
+    #[br(seek_before = SeekFrom::Start(start_offset.at(link_info_size.into())))]
+    #[getset(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _end_offset: CurrentOffset,
 }
 
 impl LinkInfo {
+    /// Create a LinkInfo for a link target which is stored on a local
+    /// volume. `local_base_path` is the full path of the link target, e.g.
+    /// `C:\Windows\notepad.exe`, as ANSI string. If
+    /// `local_base_path_unicode` is given, the path is stored as Unicode
+    /// string, too.
+    pub fn new_local(
+        volume_id: VolumeID,
+        local_base_path: impl Into<String>,
+        local_base_path_unicode: Option<String>,
+    ) -> Self {
+        let link_info_header_size = if local_base_path_unicode.is_some() {
+            0x24
+        } else {
+            0x1c
+        };
+        Self {
+            start_offset: CurrentOffset::default(),
+            link_info_size: 0,
+            link_info_header_size,
+            link_info_flags: LinkInfoFlags::VOLUME_ID_AND_LOCAL_BASE_PATH,
+            volume_id_offset: 0,
+            local_base_path_offset: 0,
+            common_network_relative_link_offset: 0,
+            common_path_suffix_offset: 0,
+            local_base_path_offset_unicode: None,
+            common_path_suffix_offset_unicode: None,
+            volume_id: Some(volume_id),
+            local_base_path: Some(local_base_path.into()),
+            common_network_relative_link: None,
+            common_path_suffix: String::new(),
+            local_base_path_unicode,
+            common_path_suffix_unicode: None,
+            _end_offset: CurrentOffset::default(),
+        }
+    }
+
+    /// Create a LinkInfo for a link target which is stored on a network
+    /// share. `common_path_suffix` is the path of the link target relative
+    /// to the share, e.g. `dir\file.exe`, as ANSI string. If
+    /// `common_path_suffix_unicode` is given, the path is stored as Unicode
+    /// string, too.
+    pub fn new_network(
+        common_network_relative_link: CommonNetworkRelativeLink,
+        common_path_suffix: impl Into<String>,
+        common_path_suffix_unicode: Option<String>,
+    ) -> Self {
+        let link_info_header_size = if common_path_suffix_unicode.is_some() {
+            0x24
+        } else {
+            0x1c
+        };
+        Self {
+            start_offset: CurrentOffset::default(),
+            link_info_size: 0,
+            link_info_header_size,
+            link_info_flags: LinkInfoFlags::COMMON_NETWORK_RELATIVE_LINK_AND_PATH_SUFFIX,
+            volume_id_offset: 0,
+            local_base_path_offset: 0,
+            common_network_relative_link_offset: 0,
+            common_path_suffix_offset: 0,
+            local_base_path_offset_unicode: None,
+            common_path_suffix_offset_unicode: None,
+            volume_id: None,
+            local_base_path: None,
+            common_network_relative_link: Some(common_network_relative_link),
+            common_path_suffix: common_path_suffix.into(),
+            local_base_path_unicode: None,
+            common_path_suffix_unicode,
+            _end_offset: CurrentOffset::default(),
+        }
+    }
+
     /// An optional, NULL–terminated string, defined by the system default code
     /// page, which is used to construct the full path to the link item or link
     /// target by appending the string in the CommonPathSuffix field. This
@@ -222,14 +348,174 @@ impl LinkInfo {
     pub fn common_path_suffix(&self) -> &str {
         self.common_path_suffix.as_ref()
     }
+
+    /// returns all ANSI strings which have a Unicode counterpart
+    pub(crate) fn string_pairs(&self) -> Vec<(&str, &str)> {
+        let mut pairs = Vec::new();
+        if let (Some(ansi), Some(unicode)) = (&self.local_base_path, &self.local_base_path_unicode)
+        {
+            pairs.push((ansi.as_str(), unicode.as_str()));
+        }
+        if let Some(unicode) = &self.common_path_suffix_unicode {
+            pairs.push((self.common_path_suffix.as_str(), unicode.as_str()));
+        }
+        if let Some(link) = &self.common_network_relative_link {
+            if let Some(unicode) = &link.net_name_unicode {
+                pairs.push((link.net_name.as_str(), unicode.as_str()));
+            }
+            if let (Some(ansi), Some(unicode)) = (&link.device_name, &link.device_name_unicode) {
+                pairs.push((ansi.as_str(), unicode.as_str()));
+            }
+        }
+        pairs
+    }
+
+    /// records the locations of the VolumeID and CommonNetworkRelativeLink
+    /// structures, whose sizes are only known after they have been read
+    pub(crate) fn record_locations(&self, ctx: &ParseContext) {
+        if let Some(volume_id) = &self.volume_id {
+            ctx.record_location(
+                "LinkInfo/VolumeID",
+                volume_id.start_offset.at(0),
+                volume_id.end_offset.at(0),
+            );
+        }
+        if let Some(link) = &self.common_network_relative_link {
+            ctx.record_location(
+                "LinkInfo/CommonNetworkRelativeLink",
+                link.start_offset.at(0),
+                link.end_offset.at(0),
+            );
+        }
+    }
 }
 
-impl From<LinkInfo> for Vec<u8> {
-    fn from(_val: LinkInfo) -> Self {
-        unimplemented!()
+/// describes the value which is expected for an offset to an optional field
+/// of a structure with `size` bytes
+fn expected_offset(is_present: bool, size: u32) -> String {
+    if is_present {
+        format!("a non-zero offset below 0x{size:x}")
+    } else {
+        "0".to_string()
     }
 }
 
+/// Writes the LinkInfo structure. All sizes, offsets and flags are computed
+/// from the contents of the structure; the values which are stored in the
+/// structure (e.g. after reading it) are ignored.
+#[cfg(feature = "binwrite")]
+impl BinWrite for LinkInfo {
+    type Args<'a> = (&'static Encoding,);
+
+    fn write_options<W: std::io::Write + std::io::Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        args: Self::Args<'_>,
+    ) -> binrw::BinResult<()> {
+        let codepage = StringEncoding::CodePage(args.0);
+        let start = writer.stream_position()?;
+
+        let has_volume_id = self.volume_id.is_some();
+        let has_unicode_strings = (has_volume_id && self.local_base_path_unicode.is_some())
+            || self.common_path_suffix_unicode.is_some();
+        let link_info_header_size: u32 = if has_unicode_strings { 0x24 } else { 0x1c };
+
+        let mut link_info_flags = LinkInfoFlags::empty();
+        link_info_flags.set(LinkInfoFlags::VOLUME_ID_AND_LOCAL_BASE_PATH, has_volume_id);
+        link_info_flags.set(
+            LinkInfoFlags::COMMON_NETWORK_RELATIVE_LINK_AND_PATH_SUFFIX,
+            self.common_network_relative_link.is_some(),
+        );
+
+        // the header is written after all offsets are known
+        writer.write_all(&vec![0; link_info_header_size as usize])?;
+
+        let mut volume_id_offset = 0;
+        let mut local_base_path_offset = 0;
+        if let Some(volume_id) = &self.volume_id {
+            volume_id_offset = relative_offset(writer, start)?;
+            volume_id.write_options(writer, endian, args)?;
+
+            local_base_path_offset = relative_offset(writer, start)?;
+            NullTerminatedString::from(self.local_base_path.clone().unwrap_or_default())
+                .write_options(writer, endian, (codepage,))?;
+        }
+
+        let mut common_network_relative_link_offset = 0;
+        if let Some(link) = &self.common_network_relative_link {
+            common_network_relative_link_offset = relative_offset(writer, start)?;
+            link.write_options(writer, endian, args)?;
+        }
+
+        let common_path_suffix_offset = relative_offset(writer, start)?;
+        NullTerminatedString::from(self.common_path_suffix.clone()).write_options(
+            writer,
+            endian,
+            (codepage,),
+        )?;
+
+        let mut local_base_path_offset_unicode = None;
+        let mut common_path_suffix_offset_unicode = None;
+        if has_unicode_strings {
+            local_base_path_offset_unicode = Some(0);
+            if has_volume_id {
+                local_base_path_offset_unicode = Some(relative_offset(writer, start)?);
+                let local_base_path = self
+                    .local_base_path_unicode
+                    .as_ref()
+                    .or(self.local_base_path.as_ref())
+                    .cloned()
+                    .unwrap_or_default();
+                NullTerminatedString::from(local_base_path).write_options(
+                    writer,
+                    endian,
+                    (StringEncoding::Unicode,),
+                )?;
+            }
+
+            common_path_suffix_offset_unicode = Some(relative_offset(writer, start)?);
+            let common_path_suffix = self
+                .common_path_suffix_unicode
+                .as_ref()
+                .unwrap_or(&self.common_path_suffix)
+                .clone();
+            NullTerminatedString::from(common_path_suffix).write_options(
+                writer,
+                endian,
+                (StringEncoding::Unicode,),
+            )?;
+        }
+
+        let link_info_size = relative_offset(writer, start)?;
+        let end = writer.stream_position()?;
+
+        writer.seek(SeekFrom::Start(start))?;
+        link_info_size.write_options(writer, endian, ())?;
+        link_info_header_size.write_options(writer, endian, ())?;
+        link_info_flags.write_options(writer, endian, ())?;
+        volume_id_offset.write_options(writer, endian, ())?;
+        local_base_path_offset.write_options(writer, endian, ())?;
+        common_network_relative_link_offset.write_options(writer, endian, ())?;
+        common_path_suffix_offset.write_options(writer, endian, ())?;
+        local_base_path_offset_unicode.write_options(writer, endian, ())?;
+        common_path_suffix_offset_unicode.write_options(writer, endian, ())?;
+        writer.seek(SeekFrom::Start(end))?;
+
+        Ok(())
+    }
+}
+
+/// returns the current position of `writer`, relative to `start`
+#[cfg(feature = "binwrite")]
+fn relative_offset<W: std::io::Seek>(writer: &mut W, start: u64) -> binrw::BinResult<u32> {
+    let pos = writer.stream_position()?;
+    u32::try_from(pos - start).map_err(|_| binrw::Error::AssertFail {
+        pos,
+        message: format!("offset 0x{:x} is too large", pos - start),
+    })
+}
+
 bitflags! {
     /// Flags that specify whether the VolumeID, LocalBasePath, LocalBasePathUnicode,
     /// and CommonNetworkRelativeLink fields are present in this structure.
@@ -277,11 +563,11 @@ impl LinkInfoFlags {
 /// resolving the link if the file is not found in its original location.
 #[derive(Clone, Debug, BinRead, Getters)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
-#[get(get = "pub")]
+#[getset(get = "pub")]
 #[allow(unused)]
-#[br(import(default_codepage: &'static Encoding))]
+#[br(import(default_codepage: &'static Encoding, ctx: &ParseContext))]
 pub struct VolumeID {
-    #[get(skip)]
+    #[getset(skip)]
     #[cfg_attr(feature = "serde", serde(skip))]
     start_offset: CurrentOffset,
     /// VolumeIDSize (4 bytes): A 32-bit, unsigned integer that specifies the
@@ -289,11 +575,17 @@ pub struct VolumeID {
     /// `0x00000010``. All offsets specified in this structure MUST be less
     /// than this value, and all strings contained in this structure MUST fit
     /// within the extent defined by this size.
-    #[br(assert(volume_id_size > 0x10))]
+    #[br(assert(ctx.check(
+        volume_id_size > 0x10,
+        start_offset.at(0),
+        || FieldError::new("VolumeIDSize", "a value greater than 0x10", format!("0x{volume_id_size:x}"))
+    )?))]
+    #[getset(skip)]
     volume_id_size: u32,
 
     /// A 32-bit, unsigned integer that specifies the type of drive the link
     /// target is stored on.
+    #[br(parse_with = read_drive_type, args(ctx))]
     drive_type: DriveType,
 
     /// A 32-bit, unsigned integer that specifies the drive serial number of
@@ -310,7 +602,16 @@ pub struct VolumeID {
     /// If the value of this field is 0x00000014, it MUST be ignored, and the
     /// value of the VolumeLabelOffsetUnicode field MUST be used to locate the
     /// volume label string.
-    #[br(assert(volume_label_offset < volume_id_size))]
+    #[br(assert(ctx.check(
+        volume_label_offset < volume_id_size,
+        start_offset.at(0x0c),
+        || FieldError::new(
+            "VolumeLabelOffset",
+            format!("an offset below 0x{volume_id_size:x}"),
+            format!("0x{volume_label_offset:x}")
+        )
+    )?))]
+    #[getset(skip)]
     volume_label_offset: u32,
 
     /// VolumeLabelOffsetUnicode (4 bytes): An optional, 32-bit, unsigned
@@ -324,37 +625,125 @@ pub struct VolumeID {
     /// field MUST NOT be present; instead, the value of the VolumeLabelOffset
     /// field MUST be used to locate the volume label string.
     #[br(if(volume_label_offset == 0x14))]
+    #[getset(skip)]
     volume_label_offset_unicode: Option<u32>,
 
     /// The label of the volume that the link target is stored on.
     #[br(
+        seek_before = SeekFrom::Start(start_offset.at(volume_label_offset_unicode.unwrap_or(volume_label_offset).into())),
         args({volume_label_offset_unicode.and(Some(StringEncoding::Unicode)).unwrap_or(StringEncoding::CodePage(default_codepage))}),
-        map=|s: NullTerminatedString| s.to_string()
+        map=|s: Located<NullTerminatedString>| s.record(ctx, if volume_label_offset_unicode.is_some() { "LinkInfo/VolumeID/VolumeLabelUnicode" } else { "LinkInfo/VolumeID/VolumeLabel" }).to_string()
     )]
     #[getset(skip)]
     volume_label: String,
 
+    /// the end of the structure, as specified by VolumeIDSize
+    #[br(seek_before = SeekFrom::Start(start_offset.at(volume_id_size.into())))]
+    #[getset(skip)]
     #[cfg_attr(feature = "serde", serde(skip))]
-    _next_offset: CurrentOffset,
+    end_offset: CurrentOffset,
 }
 
 impl VolumeID {
+    /// Create a VolumeID for a volume of type `drive_type`, which has the
+    /// serial number `drive_serial_number` and the label `volume_label`
+    pub fn new(
+        drive_type: DriveType,
+        drive_serial_number: u32,
+        volume_label: impl Into<String>,
+    ) -> Self {
+        Self {
+            start_offset: CurrentOffset::default(),
+            volume_id_size: 0,
+            drive_type,
+            drive_serial_number,
+            volume_label_offset: 0,
+            volume_label_offset_unicode: None,
+            volume_label: volume_label.into(),
+            end_offset: CurrentOffset::default(),
+        }
+    }
+
     /// The label of the volume that the link target is stored on.
     pub fn volume_label(&self) -> &str {
         self.volume_label.as_ref()
     }
 }
 
-impl From<VolumeID> for Vec<u8> {
-    fn from(_val: VolumeID) -> Self {
-        unimplemented!()
+/// Writes the VolumeID structure. The size and the label offsets are computed
+/// from the volume label. The label is stored as Unicode string if it has been
+/// read as Unicode string or if it cannot be encoded using the default code
+/// page.
+#[cfg(feature = "binwrite")]
+impl BinWrite for VolumeID {
+    type Args<'a> = (&'static Encoding,);
+
+    fn write_options<W: std::io::Write + std::io::Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        args: Self::Args<'_>,
+    ) -> binrw::BinResult<()> {
+        let start = writer.stream_position()?;
+        let (_, _, unmappable) = args.0.encode(&self.volume_label);
+        let encoding = if self.volume_label_offset_unicode.is_some() || unmappable {
+            StringEncoding::Unicode
+        } else {
+            StringEncoding::CodePage(args.0)
+        };
+
+        let (volume_label_offset, volume_label_offset_unicode) = match encoding {
+            StringEncoding::Unicode => (0x14u32, Some(0x14u32)),
+            StringEncoding::CodePage(_) => (0x10u32, None),
+        };
+
+        // the header is written after the size is known
+        writer.write_all(&vec![0; volume_label_offset as usize])?;
+        NullTerminatedString::from(self.volume_label.clone()).write_options(
+            writer,
+            endian,
+            (encoding,),
+        )?;
+        let volume_id_size = relative_offset(writer, start)?;
+        let end = writer.stream_position()?;
+
+        writer.seek(SeekFrom::Start(start))?;
+        volume_id_size.write_options(writer, endian, ())?;
+        self.drive_type.write_options(writer, endian, ())?;
+        self.drive_serial_number.write_options(writer, endian, ())?;
+        volume_label_offset.write_options(writer, endian, ())?;
+        volume_label_offset_unicode.write_options(writer, endian, ())?;
+        writer.seek(SeekFrom::Start(end))?;
+
+        Ok(())
+    }
+}
+
+/// reads the [`DriveType`]. Unknown values are treated as
+/// [`DriveType::DriveUnknown`] in lenient mode.
+fn read_drive_type<R: std::io::Read + std::io::Seek>(
+    reader: &mut R,
+    endian: binrw::Endian,
+    (ctx,): (&ParseContext,),
+) -> binrw::BinResult<DriveType> {
+    let pos = reader.stream_position()?;
+    let raw = u32::read_options(reader, endian, ())?;
+    match DriveType::from_u32(raw) {
+        Some(drive_type) => Ok(drive_type),
+        None => {
+            ctx.deviation(
+                pos,
+                FieldError::new("DriveType", "a known drive type", format!("0x{raw:x}")),
+            )?;
+            Ok(DriveType::DriveUnknown)
+        }
     }
 }
 
 /// A 32-bit, unsigned integer that specifies the type of drive the link target is stored on.
-#[derive(Clone, Debug, FromPrimitive, ToPrimitive, BinRead)]
+#[derive(Clone, Debug, Eq, PartialEq, FromPrimitive, ToPrimitive, BinRead, BinWrite)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
-#[br(repr(u32))]
+#[brw(repr(u32))]
 pub enum DriveType {
     /// The drive type cannot be determined.
     DriveUnknown = 0x00,
@@ -377,29 +766,53 @@ pub enum DriveType {
 /// UNC paths, see [MS-DFSNM] section 2.2.1.4.
 ///
 /// <https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-shllink/23bb5877-e3dd-4799-9f50-79f05f938537>
-#[derive(Clone, Debug, BinRead)]
+#[derive(Clone, Debug, BinRead, Getters)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
+#[getset(get = "pub")]
 #[allow(unused)]
-#[br(import(default_codepage: &'static Encoding))]
+#[br(import(default_codepage: &'static Encoding, ctx: &ParseContext))]
 pub struct CommonNetworkRelativeLink {
-    #[serde(skip)]
+    #[getset(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    start_offset: CurrentOffset,
+
+    #[cfg_attr(feature = "serde", serde(skip))]
     /// CommonNetworkRelativeLinkSize (4 bytes): A 32-bit, unsigned integer
     /// that specifies the size, in bytes, of the CommonNetworkRelativeLink
     /// structure. This value MUST be greater than or equal to 0x00000014. All
     /// offsets specified in this structure MUST be less than this value, and
     /// all strings contained in this structure MUST fit within the extent
     /// defined by this size.
-    #[br(assert(common_network_relative_link_size >= 0x14))]
+    #[br(assert(ctx.check(
+        common_network_relative_link_size >= 0x14,
+        start_offset.at(0),
+        || FieldError::new(
+            "CommonNetworkRelativeLinkSize",
+            "a value of at least 0x14",
+            format!("0x{common_network_relative_link_size:x}")
+        )
+    )?))]
+    #[getset(skip)]
     common_network_relative_link_size: u32,
 
     /// Flags that specify the contents of the DeviceNameOffset and
     /// NetProviderType fields.
+    #[br(args_raw = Some(ctx))]
     flags: CommonNetworkRelativeLinkFlags,
 
     /// NetNameOffset (4 bytes): A 32-bit, unsigned integer that specifies the
     /// location of the NetName field. This value is an offset, in bytes, from
     /// the start of the CommonNetworkRelativeLink structure.
-    #[br(assert(net_name_offset < common_network_relative_link_size))]
+    #[br(assert(ctx.check(
+        net_name_offset < common_network_relative_link_size,
+        start_offset.at(0x08),
+        || FieldError::new(
+            "NetNameOffset",
+            format!("an offset below 0x{common_network_relative_link_size:x}"),
+            format!("0x{net_name_offset:x}")
+        )
+    )?))]
+    #[getset(skip)]
     net_name_offset: u32,
 
     /// DeviceNameOffset (4 bytes): A 32-bit, unsigned integer that specifies
@@ -408,22 +821,29 @@ pub struct CommonNetworkRelativeLink {
     /// CommonNetworkRelativeLink structure; otherwise, this value MUST be
     /// zero.
     #[br(
-        assert(
+        assert(ctx.check(
             device_name_offset < common_network_relative_link_size &&
             if flags.has_valid_device() {
                 device_name_offset > 0
             } else {
                 device_name_offset == 0
-            }
-        )
+            },
+            start_offset.at(0x0c),
+            || FieldError::new(
+                "DeviceNameOffset",
+                expected_offset(flags.has_valid_device(), common_network_relative_link_size),
+                format!("0x{device_name_offset:x}")
+            )
+        )?)
     )]
+    #[getset(skip)]
     device_name_offset: u32,
 
     /// NetworkProviderType (4 bytes): A 32-bit, unsigned integer that
     /// specifies the type of network provider. If the ValidNetType flag is
     /// set, this value MUST be one of the following; otherwise, this value
     /// MUST be ignored.
-    #[br(map = |t| if flags.has_valid_net_type() {Some(t)} else {None})]
+    #[br(parse_with = read_network_provider_type, args(flags.has_valid_net_type(), ctx))]
     network_provider_type: Option<NetworkProviderType>,
 
     /// NetNameOffsetUnicode (4 bytes): An optional, 32-bit, unsigned integer
@@ -432,6 +852,7 @@ pub struct CommonNetworkRelativeLink {
     /// structure. This field MUST be present if the value of the NetNameOffset
     /// field is greater than 0x00000014; otherwise, this field MUST NOT be present.
     #[br(if(net_name_offset > 0x00000014))]
+    #[getset(skip)]
     net_name_offset_unicode: Option<u32>,
 
     /// DeviceNameOffsetUnicode (4 bytes): An optional, 32-bit, unsigned
@@ -441,14 +862,16 @@ pub struct CommonNetworkRelativeLink {
     /// value of the NetNameOffset field is greater than 0x00000014; otherwise,
     /// this field MUST NOT be present.
     #[br(if(net_name_offset > 0x00000014))]
+    #[getset(skip)]
     device_name_offset_unicode: Option<u32>,
 
     /// A NULL–terminated string, as defined by the system default code
     /// page, which specifies a server share path; for example,
     /// "\\server\share".
     #[br(
+        seek_before = SeekFrom::Start(start_offset.at(net_name_offset.into())),
         args(StringEncoding::CodePage(default_codepage)),
-        map=|n: NullTerminatedString| n.to_string()
+        map=|n: Located<NullTerminatedString>| n.record(ctx, "LinkInfo/CommonNetworkRelativeLink/NetName").to_string()
     )]
     net_name: String,
 
@@ -457,8 +880,9 @@ pub struct CommonNetworkRelativeLink {
     /// "D:".
     #[br(
         if(flags.has_valid_device()),
+        seek_before = SeekFrom::Start(start_offset.at(device_name_offset.into())),
         args(StringEncoding::CodePage(default_codepage)),
-        map=|n: Option<NullTerminatedString>| n.map(|s| s.to_string())
+        map=|n: Option<Located<NullTerminatedString>>| n.map(|s| s.record(ctx, "LinkInfo/CommonNetworkRelativeLink/DeviceName").to_string())
     )]
     device_name: Option<String>,
 
@@ -468,8 +892,9 @@ pub struct CommonNetworkRelativeLink {
     /// field MUST NOT be present.
     #[br(
         if(net_name_offset > 0x00000014),
+        seek_before = SeekFrom::Start(start_offset.at(net_name_offset_unicode.unwrap_or_default().into())),
         args(StringEncoding::Unicode),
-        map=|n: Option<NullTerminatedString>| n.map(|s| s.to_string())
+        map=|n: Option<Located<NullTerminatedString>>| n.map(|s| s.record(ctx, "LinkInfo/CommonNetworkRelativeLink/NetNameUnicode").to_string())
     )]
     net_name_unicode: Option<String>,
 
@@ -479,34 +904,203 @@ pub struct CommonNetworkRelativeLink {
     /// this field MUST NOT be present.
     #[br(
         if(net_name_offset > 0x00000014 && flags.has_valid_device()),
+        seek_before = SeekFrom::Start(start_offset.at(device_name_offset_unicode.unwrap_or_default().into())),
         args(StringEncoding::Unicode),
-        map=|n: Option<NullTerminatedString>| n.map(|s| s.to_string())
+        map=|n: Option<Located<NullTerminatedString>>| n.map(|s| s.record(ctx, "LinkInfo/CommonNetworkRelativeLink/DeviceNameUnicode").to_string())
     )]
     device_name_unicode: Option<String>,
+
+    /// the end of the structure, as specified by
+    /// CommonNetworkRelativeLinkSize
+    #[br(seek_before = SeekFrom::Start(start_offset.at(common_network_relative_link_size.into())))]
+    #[getset(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    end_offset: CurrentOffset,
 }
 
-impl From<CommonNetworkRelativeLink> for Vec<u8> {
-    fn from(_val: CommonNetworkRelativeLink) -> Self {
-        unimplemented!()
+/// Writes the CommonNetworkRelativeLink structure. The size, the flags and
+/// all offsets are computed from the contents of the structure. The Unicode
+/// names are written if at least one of them is present.
+#[cfg(feature = "binwrite")]
+impl BinWrite for CommonNetworkRelativeLink {
+    type Args<'a> = (&'static Encoding,);
+
+    fn write_options<W: std::io::Write + std::io::Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        args: Self::Args<'_>,
+    ) -> binrw::BinResult<()> {
+        let codepage = StringEncoding::CodePage(args.0);
+        let start = writer.stream_position()?;
+
+        let has_unicode_names =
+            self.net_name_unicode.is_some() || self.device_name_unicode.is_some();
+        let header_size: u32 = if has_unicode_names { 0x1c } else { 0x14 };
+
+        let mut flags = CommonNetworkRelativeLinkFlags::empty();
+        flags.set(
+            CommonNetworkRelativeLinkFlags::VALID_DEVICE,
+            self.device_name.is_some(),
+        );
+        flags.set(
+            CommonNetworkRelativeLinkFlags::VALID_NET_TYPE,
+            self.network_provider_type.is_some(),
+        );
+
+        // the header is written after all offsets are known
+        writer.write_all(&vec![0; header_size as usize])?;
+
+        let net_name_offset = relative_offset(writer, start)?;
+        NullTerminatedString::from(self.net_name.clone()).write_options(
+            writer,
+            endian,
+            (codepage,),
+        )?;
+
+        let mut device_name_offset = 0;
+        if let Some(device_name) = &self.device_name {
+            device_name_offset = relative_offset(writer, start)?;
+            NullTerminatedString::from(device_name.clone()).write_options(
+                writer,
+                endian,
+                (codepage,),
+            )?;
+        }
+
+        let mut net_name_offset_unicode = None;
+        let mut device_name_offset_unicode = None;
+        if has_unicode_names {
+            net_name_offset_unicode = Some(relative_offset(writer, start)?);
+            let net_name = self
+                .net_name_unicode
+                .as_ref()
+                .unwrap_or(&self.net_name)
+                .clone();
+            NullTerminatedString::from(net_name).write_options(
+                writer,
+                endian,
+                (StringEncoding::Unicode,),
+            )?;
+
+            device_name_offset_unicode = Some(0);
+            if let Some(device_name) = self
+                .device_name_unicode
+                .as_ref()
+                .or(self.device_name.as_ref())
+            {
+                device_name_offset_unicode = Some(relative_offset(writer, start)?);
+                NullTerminatedString::from(device_name.clone()).write_options(
+                    writer,
+                    endian,
+                    (StringEncoding::Unicode,),
+                )?;
+            }
+        }
+
+        let common_network_relative_link_size = relative_offset(writer, start)?;
+        let end = writer.stream_position()?;
+
+        writer.seek(SeekFrom::Start(start))?;
+        common_network_relative_link_size.write_options(writer, endian, ())?;
+        flags.write_options(writer, endian, ())?;
+        net_name_offset.write_options(writer, endian, ())?;
+        device_name_offset.write_options(writer, endian, ())?;
+        match &self.network_provider_type {
+            Some(network_provider_type) => {
+                network_provider_type.write_options(writer, endian, ())?
+            }
+            None => 0u32.write_options(writer, endian, ())?,
+        }
+        net_name_offset_unicode.write_options(writer, endian, ())?;
+        device_name_offset_unicode.write_options(writer, endian, ())?;
+        writer.seek(SeekFrom::Start(end))?;
+
+        Ok(())
     }
 }
 
 impl CommonNetworkRelativeLink {
-    /// returns the name of this link
-    pub fn name(&self) -> String {
+    /// Create a CommonNetworkRelativeLink for the server share `net_name`,
+    /// e.g. `\\server\share`, which is provided by `network_provider_type`
+    /// (usually [`NetworkProviderType::Smb`]). The flags are updated by the
+    /// setters of the optional fields.
+    pub fn new(
+        net_name: impl Into<String>,
+        network_provider_type: Option<NetworkProviderType>,
+    ) -> Self {
+        let mut flags = CommonNetworkRelativeLinkFlags::empty();
+        flags.set(
+            CommonNetworkRelativeLinkFlags::VALID_NET_TYPE,
+            network_provider_type.is_some(),
+        );
+        Self {
+            start_offset: CurrentOffset::default(),
+            common_network_relative_link_size: 0,
+            flags,
+            net_name_offset: 0,
+            device_name_offset: 0,
+            network_provider_type,
+            net_name_offset_unicode: None,
+            device_name_offset_unicode: None,
+            net_name: net_name.into(),
+            device_name: None,
+            net_name_unicode: None,
+            device_name_unicode: None,
+            end_offset: CurrentOffset::default(),
+        }
+    }
+
+    /// Set the device which the share is mapped to, e.g. the drive letter
+    /// `Z:`. The ValidDevice flag is updated accordingly.
+    pub fn set_device_name(&mut self, device_name: Option<String>) {
+        self.flags.set(
+            CommonNetworkRelativeLinkFlags::VALID_DEVICE,
+            device_name.is_some(),
+        );
+        self.device_name = device_name;
+    }
+
+    /// Set the type of the network provider. The ValidNetType flag is
+    /// updated accordingly.
+    pub fn set_network_provider_type(
+        &mut self,
+        network_provider_type: Option<NetworkProviderType>,
+    ) {
+        self.flags.set(
+            CommonNetworkRelativeLinkFlags::VALID_NET_TYPE,
+            network_provider_type.is_some(),
+        );
+        self.network_provider_type = network_provider_type;
+    }
+
+    /// Set the Unicode version of the net name, which is needed if the net
+    /// name cannot be encoded using the default code page
+    pub fn set_net_name_unicode(&mut self, net_name_unicode: Option<String>) {
+        self.net_name_unicode = net_name_unicode;
+    }
+
+    /// Set the Unicode version of the device name
+    pub fn set_device_name_unicode(&mut self, device_name_unicode: Option<String>) {
+        self.device_name_unicode = device_name_unicode;
+    }
+
+    /// returns the name of this link, which is the device name if the
+    /// ValidDevice flag is set, and the net name otherwise. Returns `None`
+    /// if the device name is missing.
+    pub fn name(&self) -> Option<String> {
         if self.flags.has_valid_device() {
             self.device_name_unicode
                 .as_ref()
                 .or(self.device_name.as_ref())
-                .expect("device name must be set, if VALID_DEVICE is set")
-                .to_string()
-        } else if self.flags.has_valid_net_type() {
-            self.net_name_unicode
-                .as_ref()
-                .unwrap_or(&self.net_name)
-                .to_string()
+                .cloned()
         } else {
-            panic!("either one of VALID_DEVICE or VALID_NET_TYPE must be set")
+            Some(
+                self.net_name_unicode
+                    .as_ref()
+                    .unwrap_or(&self.net_name)
+                    .to_string(),
+            )
         }
     }
 }
@@ -540,12 +1134,41 @@ impl CommonNetworkRelativeLinkFlags {
     }
 }
 
+/// reads the [`NetworkProviderType`], which must be ignored if the
+/// ValidNetType flag is not set. Unknown values are ignored in lenient mode.
+fn read_network_provider_type<R: std::io::Read + std::io::Seek>(
+    reader: &mut R,
+    endian: binrw::Endian,
+    (is_valid, ctx): (bool, &ParseContext),
+) -> binrw::BinResult<Option<NetworkProviderType>> {
+    let pos = reader.stream_position()?;
+    let raw = u32::read_options(reader, endian, ())?;
+    if !is_valid {
+        return Ok(None);
+    }
+
+    match NetworkProviderType::from_u32(raw) {
+        Some(network_provider_type) => Ok(Some(network_provider_type)),
+        None => {
+            ctx.deviation(
+                pos,
+                FieldError::new(
+                    "NetworkProviderType",
+                    "a known network provider type",
+                    format!("0x{raw:x}"),
+                ),
+            )?;
+            Ok(None)
+        }
+    }
+}
+
 /// A 32-bit, unsigned integer that specifies the type of network provider.
 /// <https://learn.microsoft.com/de-de/windows/win32/api/winbase/ns-winbase-file_remote_protocol_info>
 #[allow(missing_docs)]
-#[derive(Clone, Debug, FromPrimitive, ToPrimitive, BinRead)]
+#[derive(Clone, Debug, Eq, PartialEq, FromPrimitive, ToPrimitive, BinRead, BinWrite)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
-#[br(repr(u32))]
+#[brw(repr(u32))]
 pub enum NetworkProviderType {
     None = 0,
     MSNet = 0x00010000,
@@ -615,3 +1238,114 @@ pub enum NetworkProviderType {
     MsNfs = 0x420000,
     Google = 0x430000,
 }
+
+#[cfg(all(test, feature = "binwrite"))]
+mod tests {
+    use std::io::Cursor;
+
+    use binrw::{BinReaderExt, BinWrite};
+    use encoding_rs::WINDOWS_1252;
+
+    use super::LinkInfo;
+    use crate::{ParseContext, ParseOptions};
+
+    fn roundtrip(data: &[u8]) -> Vec<u8> {
+        let mut cursor = Cursor::new(data);
+        let link_info: LinkInfo = cursor
+            .read_le_args((WINDOWS_1252, &ParseContext::default()))
+            .unwrap();
+
+        let mut cursor = Cursor::new(Vec::new());
+        link_info
+            .write_le_args(&mut cursor, (WINDOWS_1252,))
+            .unwrap();
+        cursor.into_inner()
+    }
+
+    #[test]
+    fn test_local_link_info() {
+        let data = [
+            0x3c, 0x00, 0x00, 0x00, 0x1c, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x1c, 0x00,
+            0x00, 0x00, 0x2d, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3b, 0x00, 0x00, 0x00,
+            0x11, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x81, 0x8a, 0x7a, 0x30, 0x10, 0x00,
+            0x00, 0x00, 0x00, 0x43, 0x3a, 0x5c, 0x74, 0x65, 0x73, 0x74, 0x5c, 0x61, 0x2e, 0x74,
+            0x78, 0x74, 0x00, 0x00,
+        ];
+        assert_eq!(roundtrip(&data), data);
+    }
+
+    #[test]
+    fn test_network_link_info() {
+        let data = [
+            0x45, 0x00, 0x00, 0x00, 0x1c, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1c, 0x00, 0x00, 0x00, 0x3f, 0x00, 0x00, 0x00,
+            0x23, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x5c, 0x5c, 0x73, 0x65, 0x72, 0x76, 0x65, 0x72,
+            0x5c, 0x73, 0x68, 0x61, 0x72, 0x65, 0x00, 0x61, 0x2e, 0x74, 0x78, 0x74, 0x00,
+        ];
+        assert_eq!(roundtrip(&data), data);
+    }
+
+    #[test]
+    fn test_lenient_link_info() {
+        let mut data = vec![
+            0x3c, 0x00, 0x00, 0x00, 0x1c, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x1c, 0x00,
+            0x00, 0x00, 0x2d, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x3b, 0x00, 0x00, 0x00,
+            0x11, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x81, 0x8a, 0x7a, 0x30, 0x10, 0x00,
+            0x00, 0x00, 0x00, 0x43, 0x3a, 0x5c, 0x74, 0x65, 0x73, 0x74, 0x5c, 0x61, 0x2e, 0x74,
+            0x78, 0x74, 0x00, 0x00,
+        ];
+        // unknown LinkInfoFlags and DriveType
+        data[0x08] = 0x81;
+        data[0x20] = 0x99;
+
+        let result: binrw::BinResult<LinkInfo> =
+            Cursor::new(&data).read_le_args((WINDOWS_1252, &ParseContext::default()));
+        assert!(result.is_err());
+
+        let ctx = ParseContext::new(ParseOptions::lenient());
+        let link_info: LinkInfo = Cursor::new(&data)
+            .read_le_args((WINDOWS_1252, &ctx))
+            .unwrap();
+        assert_eq!(link_info.local_base_path(), Some(r"C:\test\a.txt"));
+
+        let warnings = ctx.into_warnings();
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(*warnings[0].offset(), 0x08);
+        assert_eq!(*warnings[1].offset(), 0x20);
+    }
+
+    #[test]
+    fn test_link_info_with_unusual_layout() {
+        // the CommonPathSuffix is stored first, the LocalBasePath is aligned
+        // to 4 bytes and the VolumeID is followed by padding
+        let mut data = vec![
+            0x40, 0x00, 0x00, 0x00, 0x1c, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x2e, 0x00,
+            0x00, 0x00, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1c, 0x00, 0x00, 0x00,
+            0x00, 0xff, 0xff, 0xff,
+        ];
+        data.extend_from_slice(b"C:\\test\\a.txt\0");
+        data.extend_from_slice(&[
+            0x11, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x81, 0x8a, 0x7a, 0x30, 0x10, 0x00,
+            0x00, 0x00, 0x00, 0xff,
+        ]);
+        assert_eq!(data.len(), 0x40);
+        data.extend_from_slice(b"next structure");
+
+        let mut cursor = Cursor::new(&data);
+        let link_info: LinkInfo = cursor
+            .read_le_args((WINDOWS_1252, &ParseContext::default()))
+            .unwrap();
+        assert_eq!(link_info.local_base_path(), Some(r"C:\test\a.txt"));
+        assert_eq!(link_info.common_path_suffix(), "");
+        assert_eq!(
+            *link_info
+                .volume_id()
+                .as_ref()
+                .unwrap()
+                .drive_serial_number(),
+            0x307a8a81
+        );
+        assert_eq!(cursor.position(), 0x40);
+    }
+}
@@ -1,30 +1,87 @@
 use binrw::BinRead;
+#[cfg(feature = "binwrite")]
+use binrw::BinWrite;
+use encoding_rs::Encoding;
 #[allow(unused)]
 use log::{debug, error, info, trace, warn};
 
 #[cfg(feature = "serde")]
 use serde::Serialize;
 
-use crate::{generic_types::idlist::IdList, itemid::ItemID};
+use crate::{
+    generic_types::idlist::IdList, itemid::ItemID, parse_options::ParseContext, BuildError,
+};
 
 /// The LinkTargetIDList structure specifies the target of the link. The presence of this optional
 /// structure is specified by the HasLinkTargetIDList bit (LinkFlagssection 2.1.1) in the
 /// ShellLinkHeader(section2.1).
 #[derive(Clone, Debug, Default, BinRead)]
 #[cfg_attr(feature = "serde", derive(Serialize))]
+#[br(import(default_codepage: &'static Encoding, ctx: &ParseContext))]
 pub struct LinkTargetIdList {
     /// The size, in bytes, of the IDList field.
     pub size: u16,
     /// A stored IDList structure (section 2.2.1), which contains the item ID list. An IDList
     /// structure conforms to the following ABNF \[RFC5234\]:
     ///   `IDLIST = *ITEMID TERMINALID`
-    #[br(args(size))]
+    #[br(args(size.into(), default_codepage, ctx))]
     id_list: IdList,
 }
 
 impl LinkTargetIdList {
+    /// creates a LinkTargetIDList which contains `id_list`. Returns
+    /// `None` if the list is too large.
+    pub fn new(id_list: IdList) -> Option<Self> {
+        let size = u16::try_from(id_list.size()).ok()?;
+        Some(Self { size, id_list })
+    }
+
+    /// creates the LinkTargetIDList which Windows creates for the absolute
+    /// path `path`, see [`IdList::from_windows_path`]
+    pub fn from_windows_path(path: &str) -> Result<Self, BuildError> {
+        let id_list = IdList::from_windows_path(path)?;
+        Self::new(id_list).ok_or_else(|| BuildError::InvalidTargetPath(path.to_string()))
+    }
+
     /// returns a reference to internal list of [`ItemID`] items
     pub fn id_list(&self) -> &Vec<ItemID> {
         self.id_list.item_id_list()
     }
+
+    /// renders the path of the link target, see [`IdList::path`]
+    pub fn path(&self) -> Option<String> {
+        self.id_list.path()
+    }
+
+    /// records the locations of the `ItemID` structures, given that this
+    /// structure has been read at `start`
+    pub(crate) fn record_locations(&self, start: u64, ctx: &ParseContext) {
+        // the IDList follows the IDListSize field
+        let mut offset = start + 2;
+        for (index, item_id) in self.id_list().iter().enumerate() {
+            let end = offset + u64::from(*item_id.size());
+            ctx.record_location(format!("LinkTargetIDList/ItemID[{index}]"), offset, end);
+            offset = end;
+        }
+    }
+}
+
+#[cfg(feature = "binwrite")]
+impl BinWrite for LinkTargetIdList {
+    type Args<'a> = ();
+
+    fn write_options<W: std::io::Write + std::io::Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        _args: Self::Args<'_>,
+    ) -> binrw::BinResult<()> {
+        let pos = writer.stream_position()?;
+        let size = u16::try_from(self.id_list.size()).map_err(|_| binrw::Error::AssertFail {
+            pos,
+            message: format!("IDList of {} bytes is too large", self.id_list.size()),
+        })?;
+        size.write_options(writer, endian, ())?;
+        self.id_list.write_options(writer, endian, ())
+    }
 }
@@ -0,0 +1,335 @@
+use std::{
+    cell::{Cell, RefCell},
+    fmt,
+    io::{Read, Seek},
+};
+
+use binrw::{BinRead, BinResult};
+
+use crate::error::FieldError;
+use getset::Getters;
+#[allow(unused)]
+use log::{debug, error, info, trace, warn};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+/// Options which control how a shell link is parsed
+#[derive(Clone, Debug)]
+pub struct ParseOptions {
+    /// If `true` (which is the default), every deviation from the
+    /// specification aborts parsing. Otherwise, the parser tries to salvage
+    /// as much as possible and records each deviation as [`ParseWarning`].
+    pub strict: bool,
+
+    /// Limits which protect against oversized or looping inputs, see
+    /// [`ParseLimits`]
+    pub limits: ParseLimits,
+
+    /// Selects the code page of strings which are not stored as Unicode,
+    /// see [`CodepageSelection`]
+    pub codepage_selection: CodepageSelection,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            strict: true,
+            limits: ParseLimits::default(),
+            codepage_selection: CodepageSelection::default(),
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Create options for lenient parsing, see [`ParseOptions::strict`]
+    pub fn lenient() -> Self {
+        Self {
+            strict: false,
+            ..Default::default()
+        }
+    }
+}
+
+/// Selects the code page which is used to decode strings which are not
+/// stored as Unicode, like the StringData of links without the
+/// [`LinkFlags::IS_UNICODE`](crate::LinkFlags::IS_UNICODE) flag or the
+/// LocalBasePath of the [`LinkInfo`](crate::LinkInfo). If a code page other
+/// than the `default_codepage` is selected, the link is parsed again using
+/// that code page, which is then available as
+/// [`ShellLink::default_codepage`](crate::ShellLink::default_codepage).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CodepageSelection {
+    /// Always use the `default_codepage`, e.g. because the code page of the
+    /// system which created the link is known. This is the default, so
+    /// that [`ShellLink::open`](crate::ShellLink::open) and
+    /// [`ShellLink::from_reader`](crate::ShellLink::from_reader) use the
+    /// code page which has been passed by the caller.
+    #[default]
+    Fixed,
+
+    /// Use the code page of the [`ConsoleFEDataBlock`](crate::extradata::console_fe_data::ConsoleFEDataBlock),
+    /// if there is one, and the `default_codepage` otherwise
+    FromLink,
+
+    /// Like [`CodepageSelection::FromLink`], but if there is no
+    /// ConsoleFEDataBlock, the code page is guessed by comparing strings
+    /// which are stored both as ANSI and as Unicode string, like
+    /// LocalBasePath and LocalBasePathUnicode
+    Guess,
+}
+
+/// Resource limits for parsing untrusted files, so that the memory which is
+/// used while parsing is predictable. Exceeding any limit aborts parsing
+/// with an error, in strict mode as well as in lenient mode, because the
+/// remaining data cannot be trusted to be read safely.
+#[derive(Clone, Debug)]
+pub struct ParseLimits {
+    /// The maximum size, in bytes, of a shell link. No data beyond this size
+    /// are read. The default is 16 MiB.
+    pub max_size: u64,
+
+    /// The maximum number of ItemIDs in a single IDList. The default is 1024.
+    pub max_item_ids: usize,
+
+    /// The maximum number of blocks in the ExtraData section. The default
+    /// is 256.
+    pub max_extra_data_blocks: usize,
+
+    /// The maximum number of characters of a string in the StringData
+    /// section. The default is 65535, which is the maximum the format
+    /// permits.
+    pub max_string_length: usize,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_size: 16 * 1024 * 1024,
+            max_item_ids: 1024,
+            max_extra_data_blocks: 256,
+            max_string_length: u16::MAX as usize,
+        }
+    }
+}
+
+/// A deviation from the specification which has been found while parsing
+/// a shell link in lenient mode
+#[derive(Clone, Debug, Eq, PartialEq, Getters)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[getset(get = "pub")]
+pub struct ParseWarning {
+    /// The offset, in bytes, where the deviation has been found
+    offset: u64,
+
+    /// A description of the deviation
+    message: String,
+}
+
+impl fmt::Display for ParseWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:08x}: {}", self.offset, self.message)
+    }
+}
+
+/// The location of a structure within the data a shell link has been read
+/// from
+#[derive(Clone, Debug, Eq, PartialEq, Getters)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[getset(get = "pub")]
+pub struct StructureLocation {
+    /// The path of the structure, which consists of the names used by the
+    /// specification, e.g. `LinkInfo/VolumeID/VolumeLabel`. Elements of lists
+    /// are identified by their index, e.g. `LinkTargetIDList/ItemID[0]` or
+    /// `ExtraData/Block[2]`.
+    path: String,
+
+    /// The absolute offset, in bytes, where the structure starts
+    offset: u64,
+
+    /// The size of the structure, in bytes
+    size: u64,
+}
+
+impl fmt::Display for StructureLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "0x{:08x}-0x{:08x}: {}",
+            self.offset,
+            self.offset + self.size,
+            self.path
+        )
+    }
+}
+
+/// The state which is shared by all parsers while reading a shell link. It
+/// is only needed to read single structures, like [`LinkInfo`](crate::LinkInfo),
+/// directly; [`ShellLink`](crate::ShellLink) creates it internally.
+#[derive(Debug, Default)]
+pub struct ParseContext {
+    options: ParseOptions,
+    warnings: RefCell<Vec<ParseWarning>>,
+    locations: RefCell<Vec<StructureLocation>>,
+    limit_exceeded: Cell<bool>,
+}
+
+impl ParseContext {
+    /// Create a new `ParseContext`
+    pub fn new(options: ParseOptions) -> Self {
+        Self {
+            options,
+            warnings: RefCell::new(Vec::new()),
+            locations: RefCell::new(Vec::new()),
+            limit_exceeded: Cell::new(false),
+        }
+    }
+
+    pub(crate) fn is_strict(&self) -> bool {
+        self.options.strict
+    }
+
+    pub(crate) fn limits(&self) -> &ParseLimits {
+        &self.options.limits
+    }
+
+    /// returns whether parsing continues after an error, which is the case
+    /// in lenient mode unless a limit has been exceeded, see [`ParseLimits`]
+    pub(crate) fn can_recover(&self) -> bool {
+        !self.is_strict() && !self.limit_exceeded.get()
+    }
+
+    /// returns an error if `value` exceeds the limit `max`, see [`ParseLimits`]
+    pub(crate) fn check_limit<T: PartialOrd + fmt::Display>(
+        &self,
+        field: &'static str,
+        value: T,
+        max: T,
+        offset: u64,
+    ) -> BinResult<()> {
+        if value <= max {
+            Ok(())
+        } else {
+            self.limit_exceeded.set(true);
+            Err(binrw::Error::Custom {
+                pos: offset,
+                err: Box::new(FieldError::new(
+                    field,
+                    format!("at most {max}"),
+                    value.to_string(),
+                )),
+            })
+        }
+    }
+
+    /// checks `condition`. If it is not met, an error is returned in strict
+    /// mode, and a warning is recorded in lenient mode. Returns `true` if
+    /// parsing can continue, so that this can be used in `assert` attributes.
+    pub(crate) fn check(
+        &self,
+        condition: bool,
+        offset: u64,
+        error: impl FnOnce() -> FieldError,
+    ) -> BinResult<bool> {
+        if condition {
+            Ok(true)
+        } else {
+            self.deviation(offset, error())?;
+            Ok(true)
+        }
+    }
+
+    /// reports a deviation from the specification: returns an error in
+    /// strict mode, and records a warning in lenient mode
+    pub(crate) fn deviation(&self, offset: u64, error: FieldError) -> BinResult<()> {
+        if self.is_strict() {
+            Err(binrw::Error::Custom {
+                pos: offset,
+                err: Box::new(error),
+            })
+        } else {
+            self.warn(offset, error.to_string());
+            Ok(())
+        }
+    }
+
+    /// records a warning
+    pub(crate) fn warn(&self, offset: u64, message: String) {
+        warn!("0x{offset:08x}: {message}");
+        self.warnings
+            .borrow_mut()
+            .push(ParseWarning { offset, message });
+    }
+
+    /// records that the structure identified by `path` occupies the bytes
+    /// from `start` to `end`
+    pub(crate) fn record_location(&self, path: impl Into<String>, start: u64, end: u64) {
+        self.locations.borrow_mut().push(StructureLocation {
+            path: path.into(),
+            offset: start,
+            size: end.saturating_sub(start),
+        });
+    }
+
+    /// forgets the locations of all structures which start at or after
+    /// `offset`, because the structure which contains them has been skipped
+    pub(crate) fn discard_locations(&self, offset: u64) {
+        self.locations
+            .borrow_mut()
+            .retain(|location| location.offset < offset);
+    }
+
+    /// returns the locations which have been recorded, ordered by their
+    /// offset. Enclosing structures precede the structures they contain.
+    pub(crate) fn take_locations(&self) -> Vec<StructureLocation> {
+        let mut locations = self.locations.take();
+        locations.sort_by(|a, b| a.offset.cmp(&b.offset).then(b.size.cmp(&a.size)));
+        locations
+    }
+
+    /// consumes the context and returns the warnings which have been recorded
+    pub fn into_warnings(self) -> Vec<ParseWarning> {
+        self.warnings.into_inner()
+    }
+}
+
+/// reads a reserved value, which MUST be zero
+pub(crate) fn read_reserved<R, T>(
+    reader: &mut R,
+    endian: binrw::Endian,
+    (ctx, name): (&ParseContext, &'static str),
+) -> BinResult<T>
+where
+    R: Read + Seek,
+    T: for<'a> BinRead<Args<'a> = ()> + Default + PartialEq + fmt::LowerHex,
+{
+    let pos = reader.stream_position()?;
+    let value = T::read_options(reader, endian, ())?;
+    ctx.check(value == T::default(), pos, || {
+        FieldError::new(name, "0", format!("0x{value:x}"))
+    })?;
+    Ok(value)
+}
+
+/// reads a value which MUST be `expected`. Other values are an error in all
+/// modes, which is reported at the offset of the value. `format` renders
+/// the expected and the actual value in the error.
+pub(crate) fn read_constant<R, T>(
+    reader: &mut R,
+    endian: binrw::Endian,
+    (name, expected, format): (&'static str, T, fn(&T) -> String),
+) -> BinResult<T>
+where
+    R: Read + Seek,
+    T: for<'a> BinRead<Args<'a> = ()> + PartialEq,
+{
+    let pos = reader.stream_position()?;
+    let value = T::read_options(reader, endian, ())?;
+    if value != expected {
+        return Err(binrw::Error::Custom {
+            pos,
+            err: Box::new(FieldError::new(name, format(&expected), format(&value))),
+        });
+    }
+    Ok(value)
+}
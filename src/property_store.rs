@@ -0,0 +1,541 @@
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use binrw::{BinRead, BinReaderExt, BinResult};
+use encoding_rs::Encoding;
+use getset::Getters;
+#[allow(unused)]
+use log::{debug, error, info, trace, warn};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::{FileTime, Guid};
+
+/// The format identifier of property storages whose values are identified
+/// by strings instead of integers
+pub const STRING_NAMED_FORMAT_ID: &str = "d5cdd505-2e9c-101b-9397-08002b2cf9ae";
+
+const SPS_VERSION: u32 = 0x5350_5331;
+
+const VT_VECTOR: u16 = 0x1000;
+
+/// A Serialized Property Store is a sequence of Serialized Property
+/// Storage structures, each of which contains the values of one property
+/// set.
+///
+/// <https://learn.microsoft.com/en-us/openspecs/windows_protocols/ms-propstore/3453fb82-0e4f-4c2c-bc04-64b4bd2c51ec>
+#[derive(Clone, Debug, Default, Getters)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[getset(get = "pub")]
+pub struct PropertyStore {
+    /// The property storages, each of which is identified by its format ID
+    storages: Vec<PropertyStorage>,
+}
+
+/// A Serialized Property Storage structure contains the values of all
+/// properties which belong to the same format ID.
+#[derive(Clone, Debug, Getters)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[getset(get = "pub")]
+pub struct PropertyStorage {
+    /// The format ID, which identifies the property set
+    format_id: Guid,
+
+    /// The values of this property set
+    values: Vec<PropertyValue>,
+}
+
+/// A Serialized Property Value, which consists of the name of the property
+/// and its typed value
+#[derive(Clone, Debug, Getters)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[getset(get = "pub")]
+pub struct PropertyValue {
+    /// The name of the property
+    name: PropertyName,
+
+    /// The value of the property
+    value: TypedPropertyValue,
+}
+
+/// Properties are identified either by an integer ID or, if the format ID
+/// of the storage is [`STRING_NAMED_FORMAT_ID`], by a string.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum PropertyName {
+    /// The property is identified by an integer
+    Id(u32),
+
+    /// The property is identified by a string
+    Name(String),
+}
+
+/// The value of a property, together with its type
+/// ([MS-OLEPS] section 2.15)
+#[allow(missing_docs)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum TypedPropertyValue {
+    Empty,
+    Null,
+    I1(i8),
+    I2(i16),
+    I4(i32),
+    I8(i64),
+    UI1(u8),
+    UI2(u16),
+    UI4(u32),
+    UI8(u64),
+    Int(i32),
+    UInt(u32),
+    R4(f32),
+    R8(f64),
+    Currency(i64),
+    Date(f64),
+    Error(u32),
+    Bool(bool),
+    Decimal([u8; 16]),
+    Bstr(String),
+    Lpstr(String),
+    Lpwstr(String),
+    FileTime(FileTime),
+    Blob(Vec<u8>),
+    Clsid(Guid),
+
+    /// A vector of values, all of which have the same type
+    Vector(Vec<TypedPropertyValue>),
+
+    /// A value whose type is not supported or which could not be decoded
+    Unknown {
+        /// The variant type of the value
+        vartype: u16,
+
+        /// The raw data of the value
+        data: Vec<u8>,
+    },
+}
+
+impl PropertyStore {
+    /// returns the value of the property which is identified by `format_id`
+    /// and `id`, if any
+    pub fn get(&self, format_id: &Guid, id: u32) -> Option<&TypedPropertyValue> {
+        self.storages
+            .iter()
+            .filter(|storage| storage.format_id() == format_id)
+            .flat_map(|storage| storage.values().iter())
+            .find(|value| value.name() == &PropertyName::Id(id))
+            .map(PropertyValue::value)
+    }
+}
+
+impl TypedPropertyValue {
+    /// returns the value as string, if this is a string value
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Bstr(s) | Self::Lpstr(s) | Self::Lpwstr(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl BinRead for PropertyStore {
+    type Args<'a> = (&'static Encoding,);
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        endian: binrw::Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        let mut storages = Vec::new();
+        loop {
+            let pos = reader.stream_position()?;
+            let storage_size: u32 = reader.read_le()?;
+            if storage_size == 0 {
+                break;
+            }
+
+            // the storage size includes the size field itself
+            let data = read_sized(reader, storage_size, 4, pos)?;
+            let storage = PropertyStorage::read_options(&mut Cursor::new(data), endian, args)?;
+            storages.push(storage);
+        }
+        Ok(Self { storages })
+    }
+}
+
+impl BinRead for PropertyStorage {
+    type Args<'a> = (&'static Encoding,);
+
+    fn read_options<R: Read + Seek>(
+        reader: &mut R,
+        _endian: binrw::Endian,
+        args: Self::Args<'_>,
+    ) -> BinResult<Self> {
+        let pos = reader.stream_position()?;
+        let version: u32 = reader.read_le()?;
+        if version != SPS_VERSION {
+            return Err(binrw::Error::BadMagic {
+                pos,
+                found: Box::new(version),
+            });
+        }
+
+        let format_id: Guid = reader.read_le()?;
+        let string_named = format_id.to_string() == STRING_NAMED_FORMAT_ID;
+
+        let mut values = Vec::new();
+        loop {
+            let pos = reader.stream_position()?;
+            let value_size: u32 = match reader.read_le() {
+                Ok(value_size) => value_size,
+
+                // the terminating value is missing in some files
+                Err(why) if why.is_eof() => break,
+                Err(why) => return Err(why),
+            };
+            if value_size == 0 {
+                break;
+            }
+
+            // the value size includes the size field itself
+            let data = read_sized(reader, value_size, 4, pos)?;
+            let mut value_reader = Cursor::new(data);
+
+            let name = if string_named {
+                let name_size: u32 = value_reader.read_le()?;
+                let _reserved: u8 = value_reader.read_le()?;
+                let name = read_utf16(&mut value_reader, name_size / 2)?;
+                PropertyName::Name(name)
+            } else {
+                let id: u32 = value_reader.read_le()?;
+                let _reserved: u8 = value_reader.read_le()?;
+                PropertyName::Id(id)
+            };
+
+            let value = TypedPropertyValue::read_or_unknown(&mut value_reader, args.0)?;
+            values.push(PropertyValue { name, value });
+        }
+
+        Ok(Self { format_id, values })
+    }
+}
+
+impl TypedPropertyValue {
+    /// reads a typed value. If the value cannot be decoded, the remaining
+    /// data of `reader` are returned as [`TypedPropertyValue::Unknown`]
+    fn read_or_unknown(
+        reader: &mut Cursor<Vec<u8>>,
+        default_codepage: &'static Encoding,
+    ) -> BinResult<Self> {
+        let vartype: u16 = reader.read_le()?;
+        let _padding: u16 = reader.read_le()?;
+        let start = reader.position();
+
+        match Self::read_value(reader, vartype, default_codepage) {
+            Ok(value) => Ok(value),
+            Err(why) => {
+                debug!("unable to decode property value of type 0x{vartype:04x}: {why}");
+                let data = reader
+                    .get_ref()
+                    .get(start as usize..)
+                    .unwrap_or_default()
+                    .to_vec();
+                Ok(Self::Unknown { vartype, data })
+            }
+        }
+    }
+
+    fn read_value<R: Read + Seek>(
+        reader: &mut R,
+        vartype: u16,
+        default_codepage: &'static Encoding,
+    ) -> BinResult<Self> {
+        if vartype & VT_VECTOR == VT_VECTOR {
+            let element_type = vartype & !VT_VECTOR;
+            let start = reader.stream_position()?;
+
+            // VT_EMPTY and VT_NULL have no data, so a vector of them could
+            // have any number of elements
+            if matches!(element_type, 0x0000 | 0x0001) {
+                return Err(binrw::Error::AssertFail {
+                    pos: start,
+                    message: format!("invalid vector type 0x{vartype:04x}"),
+                });
+            }
+
+            // every element has at least one byte
+            let count: u32 = reader.read_le()?;
+            let pos = reader.stream_position()?;
+            let remaining = reader.seek(SeekFrom::End(0))? - pos;
+            reader.seek(SeekFrom::Start(pos))?;
+            if u64::from(count) > remaining {
+                return Err(binrw::Error::AssertFail {
+                    pos: start,
+                    message: format!("{count} elements do not fit into {remaining} bytes"),
+                });
+            }
+            let mut elements = Vec::new();
+            for _ in 0..count {
+                let element = if element_type == 0x000c {
+                    // VT_VARIANT: every element has its own type
+                    let element_type: u16 = reader.read_le()?;
+                    let _padding: u16 = reader.read_le()?;
+                    Self::read_scalar(reader, element_type, true, default_codepage)?
+                } else {
+                    Self::read_scalar(reader, element_type, false, default_codepage)?
+                };
+                elements.push(element);
+            }
+            skip_padding(reader, start)?;
+            Ok(Self::Vector(elements))
+        } else {
+            Self::read_scalar(reader, vartype, true, default_codepage)
+        }
+    }
+
+    /// reads a single value of type `vartype`. Scalar values are padded to
+    /// a multiple of 4 bytes, while the elements of a vector are not.
+    fn read_scalar<R: Read + Seek>(
+        reader: &mut R,
+        vartype: u16,
+        padded: bool,
+        default_codepage: &'static Encoding,
+    ) -> BinResult<Self> {
+        let pos = reader.stream_position()?;
+        let value = match vartype {
+            0x0000 => Self::Empty,
+            0x0001 => Self::Null,
+            0x0002 => Self::I2(reader.read_le()?),
+            0x0003 => Self::I4(reader.read_le()?),
+            0x0004 => Self::R4(reader.read_le()?),
+            0x0005 => Self::R8(reader.read_le()?),
+            0x0006 => Self::Currency(reader.read_le()?),
+            0x0007 => Self::Date(reader.read_le()?),
+            0x0008 => Self::Bstr(read_codepage_string(reader, default_codepage)?),
+            0x000a => Self::Error(reader.read_le()?),
+            0x000b => Self::Bool(reader.read_le::<u16>()? != 0),
+            0x000e => Self::Decimal(reader.read_le()?),
+            0x0010 => Self::I1(reader.read_le()?),
+            0x0011 => Self::UI1(reader.read_le()?),
+            0x0012 => Self::UI2(reader.read_le()?),
+            0x0013 => Self::UI4(reader.read_le()?),
+            0x0014 => Self::I8(reader.read_le()?),
+            0x0015 => Self::UI8(reader.read_le()?),
+            0x0016 => Self::Int(reader.read_le()?),
+            0x0017 => Self::UInt(reader.read_le()?),
+            0x001e => Self::Lpstr(read_codepage_string(reader, default_codepage)?),
+            0x001f => {
+                let length: u32 = reader.read_le()?;
+                let value = read_utf16(reader, length)?;
+                skip_padding(reader, pos)?;
+                Self::Lpwstr(value)
+            }
+            0x0040 => Self::FileTime(reader.read_le()?),
+            0x0041 => {
+                let size: u32 = reader.read_le()?;
+                let data = read_sized(reader, size, 0, pos)?;
+                skip_padding(reader, pos)?;
+                Self::Blob(data)
+            }
+            0x0048 => Self::Clsid(reader.read_le()?),
+            _ => {
+                return Err(binrw::Error::AssertFail {
+                    pos,
+                    message: format!("unsupported property type 0x{vartype:04x}"),
+                })
+            }
+        };
+
+        if padded {
+            skip_padding(reader, pos)?;
+        }
+        Ok(value)
+    }
+}
+
+/// reads `size - header_size` bytes; `header_size` is the number of bytes
+/// which are included in `size`, but which have already been read
+fn read_sized<R: Read + Seek>(
+    reader: &mut R,
+    size: u32,
+    header_size: u32,
+    pos: u64,
+) -> BinResult<Vec<u8>> {
+    let size = size
+        .checked_sub(header_size)
+        .ok_or_else(|| binrw::Error::AssertFail {
+            pos,
+            message: format!("invalid size: {size}"),
+        })?;
+
+    let mut data = Vec::new();
+    reader.by_ref().take(size.into()).read_to_end(&mut data)?;
+    if data.len() != size as usize {
+        return Err(binrw::Error::AssertFail {
+            pos,
+            message: format!("expected {size} bytes, but found only {}", data.len()),
+        });
+    }
+    Ok(data)
+}
+
+/// reads a CodePageString, which starts with its size in bytes
+fn read_codepage_string<R: Read + Seek>(
+    reader: &mut R,
+    default_codepage: &'static Encoding,
+) -> BinResult<String> {
+    let pos = reader.stream_position()?;
+    let size: u32 = reader.read_le()?;
+    let data = read_sized(reader, size, 0, pos)?;
+    skip_padding(reader, pos)?;
+
+    let end = data.iter().position(|b| *b == 0).unwrap_or(data.len());
+    let (value, _, _) = default_codepage.decode(&data[..end]);
+    Ok(value.into_owned())
+}
+
+/// reads `length` UTF-16 characters and strips the terminating NULL, if any
+fn read_utf16<R: Read + Seek>(reader: &mut R, length: u32) -> BinResult<String> {
+    let pos = reader.stream_position()?;
+    let data = read_sized(reader, length.saturating_mul(2), 0, pos)?;
+    let chars: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|c| *c != 0)
+        .collect();
+    Ok(String::from_utf16_lossy(&chars))
+}
+
+/// skips the padding which follows a value which started at `start`, so
+/// that the next value starts at a multiple of 4 bytes relative to `start`
+fn skip_padding<R: Read + Seek>(reader: &mut R, start: u64) -> BinResult<()> {
+    let pos = reader.stream_position()?;
+    let padding = (4 - (pos - start) % 4) % 4;
+    reader.seek(SeekFrom::Current(padding as i64))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use binrw::BinReaderExt;
+    use encoding_rs::WINDOWS_1252;
+    use uuid::Uuid;
+
+    use super::{PropertyName, PropertyStore, TypedPropertyValue};
+    use crate::Guid;
+
+    fn storage(format_id: &str, values: &[Vec<u8>]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(&0x5350_5331u32.to_le_bytes());
+        data.extend_from_slice(&Uuid::parse_str(format_id).unwrap().to_bytes_le());
+        for value in values {
+            data.extend_from_slice(&(value.len() as u32 + 4).to_le_bytes());
+            data.extend_from_slice(value);
+        }
+        data.extend_from_slice(&0u32.to_le_bytes());
+
+        let mut storage = (data.len() as u32 + 4).to_le_bytes().to_vec();
+        storage.extend(data);
+        storage
+    }
+
+    fn utf16(s: &str) -> Vec<u8> {
+        s.encode_utf16()
+            .chain(Some(0))
+            .flat_map(u16::to_le_bytes)
+            .collect()
+    }
+
+    #[test]
+    fn test_integer_named_values() {
+        let format_id = "9f4c2855-9f79-4b39-a8d0-e1d42de1d5f3";
+
+        // VT_LPWSTR "App" (4 characters including NULL, padded to 4 bytes)
+        let mut lpwstr = vec![5, 0, 0, 0, 0, 0x1f, 0, 0, 0, 4, 0, 0, 0];
+        lpwstr.extend(utf16("App"));
+
+        // VT_VECTOR | VT_UI4
+        let mut vector = vec![6, 0, 0, 0, 0, 0x13, 0x10, 0, 0, 2, 0, 0, 0];
+        vector.extend_from_slice(&1u32.to_le_bytes());
+        vector.extend_from_slice(&2u32.to_le_bytes());
+
+        // VT_BOOL
+        let boolean = vec![7, 0, 0, 0, 0, 0x0b, 0, 0, 0, 0xff, 0xff, 0, 0];
+
+        let mut data = storage(format_id, &[lpwstr, vector, boolean]);
+        data.extend_from_slice(&0u32.to_le_bytes());
+
+        let store: PropertyStore = Cursor::new(data).read_le_args((WINDOWS_1252,)).unwrap();
+        let format_id = Guid::from(Uuid::parse_str(format_id).unwrap());
+
+        assert_eq!(store.get(&format_id, 5).unwrap().as_str(), Some("App"));
+        match store.get(&format_id, 6).unwrap() {
+            TypedPropertyValue::Vector(values) => {
+                assert!(matches!(
+                    values[..],
+                    [TypedPropertyValue::UI4(1), TypedPropertyValue::UI4(2)]
+                ))
+            }
+            other => panic!("expected a vector, found {other:?}"),
+        }
+        assert!(matches!(
+            store.get(&format_id, 7).unwrap(),
+            TypedPropertyValue::Bool(true)
+        ));
+    }
+
+    #[test]
+    fn test_huge_vectors() {
+        let format_id = "9f4c2855-9f79-4b39-a8d0-e1d42de1d5f3";
+
+        // VT_VECTOR | VT_EMPTY and VT_VECTOR | VT_UI4 with 50,000,000 elements
+        let mut empty = vec![5, 0, 0, 0, 0, 0x00, 0x10, 0, 0];
+        empty.extend_from_slice(&50_000_000u32.to_le_bytes());
+        let mut ui4 = vec![6, 0, 0, 0, 0, 0x13, 0x10, 0, 0];
+        ui4.extend_from_slice(&50_000_000u32.to_le_bytes());
+        ui4.extend_from_slice(&1u32.to_le_bytes());
+
+        let mut data = storage(format_id, &[empty, ui4]);
+        data.extend_from_slice(&0u32.to_le_bytes());
+
+        let store: PropertyStore = Cursor::new(data).read_le_args((WINDOWS_1252,)).unwrap();
+        let format_id = Guid::from(Uuid::parse_str(format_id).unwrap());
+        assert!(matches!(
+            store.get(&format_id, 5).unwrap(),
+            TypedPropertyValue::Unknown {
+                vartype: 0x1000,
+                ..
+            }
+        ));
+        assert!(matches!(
+            store.get(&format_id, 6).unwrap(),
+            TypedPropertyValue::Unknown {
+                vartype: 0x1013,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_string_named_values() {
+        let name = utf16("key");
+        let mut value = (name.len() as u32).to_le_bytes().to_vec();
+        value.push(0);
+        value.extend(name);
+        // unsupported type VT_STREAM
+        value.extend_from_slice(&[0x42, 0, 0, 0, 1, 2, 3, 4]);
+
+        let mut data = storage(super::STRING_NAMED_FORMAT_ID, &[value]);
+        data.extend_from_slice(&0u32.to_le_bytes());
+
+        let store: PropertyStore = Cursor::new(data).read_le_args((WINDOWS_1252,)).unwrap();
+        let value = &store.storages()[0].values()[0];
+        assert_eq!(value.name(), &PropertyName::Name("key".to_string()));
+        assert!(matches!(
+            value.value(),
+            TypedPropertyValue::Unknown { vartype: 0x42, data } if data == &[1, 2, 3, 4]
+        ));
+    }
+}
@@ -0,0 +1,92 @@
+use std::{
+    fmt,
+    io::{Cursor, Read, Seek, SeekFrom, Write},
+};
+
+use binrw::{BinResult, BinWrite, Endian};
+
+/// The raw bytes of a structure which has been read from a file. They are
+/// only kept if they differ from the serialization of the parsed structure,
+/// e.g. because of padding or an unusual order of fields. If the structure
+/// has not been modified when it is written, the raw bytes are written
+/// instead of the serialization, so that the file is preserved byte by byte.
+#[derive(Clone)]
+pub(crate) struct RawBytes {
+    raw: Vec<u8>,
+    canonical: Vec<u8>,
+}
+
+impl fmt::Debug for RawBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RawBytes({} bytes)", self.raw.len())
+    }
+}
+
+impl RawBytes {
+    /// reads the bytes between `start` and the current position of
+    /// `reader`, from which `value` has been parsed. Returns `None` if
+    /// there is no need to keep them.
+    pub(crate) fn capture<'a, R, T>(
+        reader: &mut R,
+        start: u64,
+        value: &T,
+        args: T::Args<'a>,
+    ) -> BinResult<Option<Self>>
+    where
+        R: Read + Seek,
+        T: BinWrite,
+    {
+        let end = reader.stream_position()?;
+        reader.seek(SeekFrom::Start(start))?;
+        let mut raw = Vec::new();
+        reader.by_ref().take(end - start).read_to_end(&mut raw)?;
+
+        // if the structure cannot be written, it cannot be written
+        // unchanged either
+        let canonical = match serialize(value, args) {
+            Ok(canonical) => canonical,
+            Err(_) => return Ok(None),
+        };
+
+        if canonical == raw {
+            Ok(None)
+        } else {
+            Ok(Some(Self { raw, canonical }))
+        }
+    }
+}
+
+/// The raw bytes of the sections of a shell link, see [`RawBytes`]
+#[derive(Clone, Debug, Default)]
+pub(crate) struct RawSections {
+    pub(crate) header: Option<RawBytes>,
+    pub(crate) linktarget_id_list: Option<RawBytes>,
+    pub(crate) link_info: Option<RawBytes>,
+    pub(crate) string_data: Option<RawBytes>,
+}
+
+fn serialize<T: BinWrite>(value: &T, args: T::Args<'_>) -> BinResult<Vec<u8>> {
+    let mut cursor = Cursor::new(Vec::new());
+    value.write_options(&mut cursor, Endian::Little, args)?;
+    Ok(cursor.into_inner())
+}
+
+/// writes `value`, or the raw bytes it has been read from if it has not
+/// been modified since then
+pub(crate) fn write_preserving<W, T>(
+    writer: &mut W,
+    value: &T,
+    args: T::Args<'_>,
+    raw: Option<&RawBytes>,
+) -> BinResult<()>
+where
+    W: Write + Seek,
+    T: BinWrite,
+{
+    let bytes = serialize(value, args)?;
+    match raw {
+        Some(raw) if raw.canonical == bytes => writer.write_all(&raw.raw)?,
+        _ => writer.write_all(&bytes)?,
+    }
+    Ok(())
+}
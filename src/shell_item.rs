@@ -0,0 +1,112 @@
+use std::io::Cursor;
+
+use binrw::BinReaderExt;
+use encoding_rs::Encoding;
+#[allow(unused)]
+use log::{debug, error, info, trace, warn};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use self::{
+    control_panel::ControlPanelShellItem, file_entry::FileEntryShellItem,
+    network_location::NetworkLocationShellItem, root_folder::RootFolderShellItem,
+    uri::UriShellItem, volume::VolumeShellItem,
+};
+
+/// The ControlPanelShellItem structure identifies a control panel applet.
+pub mod control_panel;
+
+/// Extension blocks, which can be appended to the data of some shell items.
+pub mod extension_block;
+
+/// The FileEntryShellItem structure identifies a file or a directory.
+pub mod file_entry;
+
+/// The NetworkLocationShellItem structure identifies a network resource.
+pub mod network_location;
+
+/// The RootFolderShellItem structure identifies a shell folder, like
+/// "My Computer".
+pub mod root_folder;
+
+/// The UriShellItem structure identifies a resource by its URI.
+pub mod uri;
+
+/// The VolumeShellItem structure identifies a drive.
+pub mod volume;
+
+/// The data of an [`ItemID`](crate::ItemID), decoded based on its class type
+/// indicator, which is the first byte of the data.
+///
+/// The format of shell items is not specified by Microsoft; this
+/// implementation is based on
+/// <https://github.com/libyal/libfwsi/blob/main/documentation/Windows%20Shell%20Item%20format.asciidoc>
+#[allow(missing_docs)]
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum ShellItem {
+    RootFolder(RootFolderShellItem),
+    Volume(VolumeShellItem),
+    FileEntry(FileEntryShellItem),
+    NetworkLocation(NetworkLocationShellItem),
+    Uri(UriShellItem),
+    ControlPanel(ControlPanelShellItem),
+
+    /// The raw data of a shell item whose type is unknown or which could not
+    /// be decoded
+    Raw(Vec<u8>),
+}
+
+impl Default for ShellItem {
+    fn default() -> Self {
+        Self::Raw(Vec::new())
+    }
+}
+
+impl ShellItem {
+    /// decodes the data of an [`ItemID`](crate::ItemID). If the data cannot
+    /// be decoded, [`ShellItem::Raw`] is returned.
+    pub fn parse(data: &[u8], default_codepage: &'static Encoding) -> Self {
+        let class_type = match data.first() {
+            Some(class_type) => *class_type,
+            None => return Self::Raw(Vec::new()),
+        };
+
+        let mut cursor = Cursor::new(data);
+        let item = match class_type {
+            0x1f => cursor.read_le().map(Self::RootFolder),
+            0x20..=0x2f => cursor.read_le_args((default_codepage,)).map(Self::Volume),
+            0x30..=0x3f => cursor
+                .read_le_args((default_codepage,))
+                .map(Self::FileEntry),
+            0x40..=0x4f => cursor
+                .read_le_args((default_codepage,))
+                .map(Self::NetworkLocation),
+            0x61 => cursor.read_le_args((default_codepage,)).map(Self::Uri),
+            0x71 => cursor.read_le().map(Self::ControlPanel),
+            _ => return Self::Raw(data.to_vec()),
+        };
+
+        match item {
+            Ok(item) => item,
+            Err(why) => {
+                debug!("unable to decode shell item of class type 0x{class_type:02x}: {why}");
+                Self::Raw(data.to_vec())
+            }
+        }
+    }
+
+    /// returns the class type indicator of this shell item, if any
+    pub fn class_type(&self) -> Option<u8> {
+        match self {
+            Self::RootFolder(item) => Some(*item.class_type()),
+            Self::Volume(item) => Some(*item.class_type()),
+            Self::FileEntry(item) => Some(*item.class_type()),
+            Self::NetworkLocation(item) => Some(*item.class_type()),
+            Self::Uri(item) => Some(*item.class_type()),
+            Self::ControlPanel(item) => Some(*item.class_type()),
+            Self::Raw(data) => data.first().copied(),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use binrw::BinRead;
+use getset::Getters;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::Guid;
+
+/// A control panel shell item (class type indicator 0x71) identifies a
+/// control panel applet by its class identifier.
+#[derive(Clone, Debug, BinRead, Getters)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[br(assert(class_type == 0x71))]
+#[getset(get = "pub")]
+#[allow(unused)]
+pub struct ControlPanelShellItem {
+    /// The class type indicator, which is always 0x71
+    class_type: u8,
+
+    #[getset(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    unknown: [u8; 11],
+
+    /// The class identifier (CLSID) of the control panel applet
+    control_panel_id: Guid,
+}
@@ -0,0 +1,237 @@
+use std::io::Cursor;
+
+use binrw::{BinRead, BinReaderExt};
+use encoding_rs::Encoding;
+use getset::Getters;
+#[allow(unused)]
+use log::{debug, error, info, trace, warn};
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::{
+    strings::{NullTerminatedString, StringEncoding},
+    DosDateTime,
+};
+
+/// The signature of the file entry extension block
+pub const FILE_ENTRY_EXTENSION_SIGNATURE: u32 = 0xbeef0004;
+
+/// An extension block, which can be appended to the data of some shell items.
+/// Every extension block starts with its size (u16), a version (u16) and a
+/// signature (u32), which is usually of the form `0xBEEFxxxx`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub enum ExtensionBlock {
+    /// The file entry extension block (signature `0xBEEF0004`)
+    FileEntry(FileEntryExtension),
+
+    /// An extension block whose signature is unknown or which could not be
+    /// decoded
+    Unknown {
+        /// The signature of the extension block
+        signature: u32,
+
+        /// The version of the extension block
+        version: u16,
+
+        /// The complete data of the extension block, including its header
+        data: Vec<u8>,
+    },
+}
+
+impl ExtensionBlock {
+    /// returns the signature of this extension block
+    pub fn signature(&self) -> u32 {
+        match self {
+            Self::FileEntry(_) => FILE_ENTRY_EXTENSION_SIGNATURE,
+            Self::Unknown { signature, .. } => *signature,
+        }
+    }
+
+    /// parses a sequence of extension blocks, decoding strings which are not
+    /// stored as Unicode using `default_codepage`. Parsing stops at the first
+    /// block whose size is invalid.
+    pub fn parse_all(data: &[u8], default_codepage: &'static Encoding) -> Vec<Self> {
+        let mut blocks = Vec::new();
+        let mut remaining = data;
+
+        while remaining.len() >= 8 {
+            let size = usize::from(u16::from_le_bytes([remaining[0], remaining[1]]));
+            if size < 8 || size > remaining.len() {
+                break;
+            }
+
+            let (block, rest) = remaining.split_at(size);
+            blocks.push(Self::parse(block, default_codepage));
+            remaining = rest;
+        }
+
+        if !remaining.is_empty() {
+            trace!("ignoring {} bytes after extension blocks", remaining.len());
+        }
+        blocks
+    }
+
+    fn parse(data: &[u8], default_codepage: &'static Encoding) -> Self {
+        let version = u16::from_le_bytes([data[2], data[3]]);
+        let signature = u32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+
+        let block = match signature {
+            FILE_ENTRY_EXTENSION_SIGNATURE => Cursor::new(data)
+                .read_le_args((default_codepage,))
+                .map(Self::FileEntry),
+            _ => {
+                return Self::Unknown {
+                    signature,
+                    version,
+                    data: data.to_vec(),
+                }
+            }
+        };
+
+        block.unwrap_or_else(|why| {
+            debug!("unable to decode extension block 0x{signature:08x}: {why}");
+            Self::Unknown {
+                signature,
+                version,
+                data: data.to_vec(),
+            }
+        })
+    }
+}
+
+/// The file entry extension block (signature `0xBEEF0004`) is appended to
+/// file entry shell items since Windows XP. It contains the long name of the
+/// file or directory and, since version 7 (Windows Vista), the NTFS file
+/// reference.
+///
+/// <https://github.com/libyal/libfwsi/blob/main/documentation/Windows%20Shell%20Item%20format.asciidoc#extension_block_0xbeef0004>
+#[derive(Clone, Debug, BinRead, Getters)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[br(
+    import(default_codepage: &'static Encoding),
+    assert(signature == FILE_ENTRY_EXTENSION_SIGNATURE)
+)]
+#[getset(get = "pub")]
+#[allow(unused)]
+pub struct FileEntryExtension {
+    /// The size of the extension block
+    size: u16,
+
+    /// The version of the extension block
+    version: u16,
+
+    /// The signature of the extension block
+    signature: u32,
+
+    /// The creation time of the file or directory
+    creation_time: DosDateTime,
+
+    /// The last access time of the file or directory
+    access_time: DosDateTime,
+
+    #[getset(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    identifier: u16,
+
+    #[getset(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[br(if(version >= 7))]
+    unknown1: u16,
+
+    /// The NTFS file reference, which consists of the MFT entry index (lower
+    /// 48 bits) and the sequence number (upper 16 bits). Only present since
+    /// version 7.
+    #[br(if(version >= 7))]
+    file_reference: Option<u64>,
+
+    #[getset(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[br(if(version >= 7))]
+    unknown2: u64,
+
+    #[getset(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[br(if(version >= 3))]
+    long_string_size: u16,
+
+    #[getset(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[br(if(version >= 9))]
+    unknown3: u32,
+
+    #[getset(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    #[br(if(version >= 8))]
+    unknown4: u32,
+
+    /// The long name of the file or directory
+    #[br(
+        if(version >= 3),
+        args(StringEncoding::Unicode),
+        map=|n: Option<NullTerminatedString>| n.map(|n| n.to_string())
+    )]
+    long_name: Option<String>,
+
+    /// The localized name of the file or directory, if any
+    #[br(
+        if(version >= 3 && long_string_size > 0),
+        args(if version >= 7 {
+            StringEncoding::Unicode
+        } else {
+            StringEncoding::CodePage(default_codepage)
+        }),
+        map=|n: Option<NullTerminatedString>| n.map(|n| n.to_string())
+    )]
+    localized_name: Option<String>,
+}
+
+impl FileEntryExtension {
+    /// returns the index of the MFT entry of the file or directory, if the
+    /// NTFS file reference is present
+    pub fn mft_entry(&self) -> Option<u64> {
+        self.file_reference.map(|r| r & 0x0000_ffff_ffff_ffff)
+    }
+
+    /// returns the sequence number of the MFT entry of the file or
+    /// directory, if the NTFS file reference is present
+    pub fn mft_sequence(&self) -> Option<u16> {
+        self.file_reference.map(|r| (r >> 48) as u16)
+    }
+}
+
+impl FileEntryExtension {
+    /// encodes a version 9 file entry extension block, as it is created by
+    /// Windows 8 and later, without NTFS file reference. `item_offset` is the
+    /// offset of this block within its shell item.
+    pub(crate) fn encode(
+        long_name: &str,
+        creation_time: DosDateTime,
+        access_time: DosDateTime,
+        item_offset: u16,
+    ) -> Vec<u8> {
+        let mut data = Vec::new();
+        // the size is set below
+        data.extend_from_slice(&0u16.to_le_bytes());
+        data.extend_from_slice(&9u16.to_le_bytes());
+        data.extend_from_slice(&FILE_ENTRY_EXTENSION_SIGNATURE.to_le_bytes());
+        for datetime in [creation_time, access_time] {
+            data.extend_from_slice(&datetime.date().to_le_bytes());
+            data.extend_from_slice(&datetime.time().to_le_bytes());
+        }
+        // identifier
+        data.extend_from_slice(&0x2eu16.to_le_bytes());
+        // unknown1, file reference, unknown2, long string size, unknown3
+        // and unknown4
+        data.resize(data.len() + 2 + 8 + 8 + 2 + 4 + 4, 0);
+        for c in long_name.encode_utf16().chain([0]) {
+            data.extend_from_slice(&c.to_le_bytes());
+        }
+        data.extend_from_slice(&item_offset.to_le_bytes());
+
+        let size = u16::try_from(data.len()).unwrap_or(u16::MAX);
+        data[..2].copy_from_slice(&size.to_le_bytes());
+        data
+    }
+}
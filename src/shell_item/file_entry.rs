@@ -0,0 +1,258 @@
+use binrw::{BinRead, BinResult};
+use encoding_rs::Encoding;
+use getset::Getters;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::{
+    strings::{NullTerminatedString, StringEncoding},
+    DosDateTime, FileAttributeFlags,
+};
+
+use super::extension_block::{ExtensionBlock, FileEntryExtension};
+
+/// A file entry shell item (class type indicator 0x30 - 0x3F) identifies a
+/// file or a directory in a file system.
+#[derive(Clone, Debug, BinRead, Getters)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[br(import(default_codepage: &'static Encoding), assert(class_type & 0xf0 == 0x30))]
+#[getset(get = "pub")]
+#[allow(unused)]
+pub struct FileEntryShellItem {
+    /// The class type indicator, which also contains some flags; see
+    /// [`FileEntryShellItem::is_directory`], [`FileEntryShellItem::is_file`]
+    /// and [`FileEntryShellItem::has_unicode_name`]
+    class_type: u8,
+
+    #[getset(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    unknown: u8,
+
+    /// The size of the file, which is zero for directories
+    file_size: u32,
+
+    /// The last modification time of the file or directory
+    modification_time: DosDateTime,
+
+    /// The attributes of the file or directory
+    #[br(map=|a: u16| FileAttributeFlags::from_bits_retain(a.into()))]
+    file_attributes: FileAttributeFlags,
+
+    /// The primary name of the file or directory. This is usually the
+    /// short (8.3) name
+    #[br(
+        args(if class_type & 0x04 == 0x04 {
+            StringEncoding::Unicode
+        } else {
+            StringEncoding::CodePage(default_codepage)
+        }),
+        map=|n: NullTerminatedString| n.to_string()
+    )]
+    primary_name: String,
+
+    /// The extension blocks which follow the primary name
+    #[br(align_before = 2, parse_with = parse_extension_blocks, args(default_codepage))]
+    extension_blocks: Vec<ExtensionBlock>,
+}
+
+#[binrw::parser(reader)]
+fn parse_extension_blocks(default_codepage: &'static Encoding) -> BinResult<Vec<ExtensionBlock>> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    Ok(ExtensionBlock::parse_all(&data, default_codepage))
+}
+
+impl FileEntryShellItem {
+    /// returns `true` if this item identifies a directory
+    pub fn is_directory(&self) -> bool {
+        self.class_type & 0x01 == 0x01
+    }
+
+    /// returns `true` if this item identifies a file
+    pub fn is_file(&self) -> bool {
+        self.class_type & 0x02 == 0x02
+    }
+
+    /// returns `true` if the primary name is stored as Unicode string
+    pub fn has_unicode_name(&self) -> bool {
+        self.class_type & 0x04 == 0x04
+    }
+
+    /// returns the file entry extension block (`0xBEEF0004`), if present
+    pub fn file_entry_extension(&self) -> Option<&FileEntryExtension> {
+        self.extension_blocks.iter().find_map(|block| match block {
+            ExtensionBlock::FileEntry(extension) => Some(extension),
+            _ => None,
+        })
+    }
+
+    /// returns the long name of the file or directory, if present. Use
+    /// [`FileEntryShellItem::primary_name`] as fallback.
+    pub fn long_name(&self) -> Option<&str> {
+        self.file_entry_extension()
+            .and_then(|extension| extension.long_name().as_deref())
+    }
+
+    /// returns the creation time of the file or directory, if present
+    pub fn creation_time(&self) -> Option<DosDateTime> {
+        self.file_entry_extension()
+            .map(|extension| *extension.creation_time())
+    }
+
+    /// returns the last access time of the file or directory, if present
+    pub fn access_time(&self) -> Option<DosDateTime> {
+        self.file_entry_extension()
+            .map(|extension| *extension.access_time())
+    }
+
+    /// returns the index of the MFT entry of the file or directory, if
+    /// present
+    pub fn mft_entry(&self) -> Option<u64> {
+        self.file_entry_extension()
+            .and_then(FileEntryExtension::mft_entry)
+    }
+
+    /// returns the sequence number of the MFT entry of the file or
+    /// directory, if present
+    pub fn mft_sequence(&self) -> Option<u16> {
+        self.file_entry_extension()
+            .and_then(FileEntryExtension::mft_sequence)
+    }
+}
+
+/// The metadata of a file or directory, which is stored in a new file entry
+/// shell item
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct FileEntryMetadata {
+    pub(crate) is_directory: bool,
+    pub(crate) file_size: u32,
+    pub(crate) file_attributes: FileAttributeFlags,
+    pub(crate) modification_time: DosDateTime,
+    pub(crate) creation_time: DosDateTime,
+    pub(crate) access_time: DosDateTime,
+}
+
+impl FileEntryMetadata {
+    /// returns the metadata of a directory, or of a file, without size and
+    /// timestamps
+    pub(crate) fn new(is_directory: bool) -> Self {
+        Self {
+            is_directory,
+            file_size: 0,
+            file_attributes: if is_directory {
+                FileAttributeFlags::FILE_ATTRIBUTE_DIRECTORY
+            } else {
+                FileAttributeFlags::FILE_ATTRIBUTE_ARCHIVE
+            },
+            modification_time: DosDateTime::default(),
+            creation_time: DosDateTime::default(),
+            access_time: DosDateTime::default(),
+        }
+    }
+}
+
+impl FileEntryShellItem {
+    /// encodes a file entry shell item for the file or directory `name`,
+    /// without the size of the ItemID. Like Windows does, the short name is
+    /// used as primary name, and the long name is stored in a file entry
+    /// extension block.
+    pub(crate) fn encode(name: &str, metadata: &FileEntryMetadata) -> Vec<u8> {
+        let class_type = if metadata.is_directory { 0x31 } else { 0x32 };
+        let mut data = vec![class_type, 0];
+        data.extend_from_slice(&metadata.file_size.to_le_bytes());
+        data.extend_from_slice(&metadata.modification_time.date().to_le_bytes());
+        data.extend_from_slice(&metadata.modification_time.time().to_le_bytes());
+        // only the lower 16 bits of the attributes are stored
+        data.extend_from_slice(&(metadata.file_attributes.bits() as u16).to_le_bytes());
+        data.extend_from_slice(short_name(name).as_bytes());
+        data.push(0);
+        if data.len() % 2 == 1 {
+            data.push(0);
+        }
+
+        // the offset is relative to the start of the ItemID, which begins
+        // with its size
+        let item_offset = u16::try_from(data.len() + 2).unwrap_or(u16::MAX);
+        data.extend(FileEntryExtension::encode(
+            name,
+            metadata.creation_time,
+            metadata.access_time,
+            item_offset,
+        ));
+        data
+    }
+}
+
+/// the characters which are allowed in short names, besides letters and
+/// digits
+const SHORT_NAME_CHARACTERS: &str = "!#$%&'()-@^_`{}~";
+
+/// returns `true` if `name` is a valid short (8.3) name, so that Windows does
+/// not generate another short name for it
+fn is_short_name(name: &str) -> bool {
+    let is_valid = |part: &str, max: usize| {
+        !part.is_empty()
+            && part.len() <= max
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || SHORT_NAME_CHARACTERS.contains(c))
+    };
+    match name.split_once('.') {
+        Some((base, extension)) => is_valid(base, 8) && is_valid(extension, 3),
+        None => is_valid(name, 8),
+    }
+}
+
+/// returns the short (8.3) name which Windows generates for `name`, assuming
+/// that no other file in the same directory has a similar short name. Names
+/// which are valid short names are returned unchanged.
+pub(crate) fn short_name(name: &str) -> String {
+    if is_short_name(name) {
+        return name.to_string();
+    }
+
+    let (base, extension) = match name.rfind('.') {
+        Some(index) if index > 0 => (&name[..index], &name[index + 1..]),
+        _ => (name, ""),
+    };
+    let convert = |part: &str, max: usize| -> String {
+        part.chars()
+            .filter(|c| !matches!(c, ' ' | '.'))
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || SHORT_NAME_CHARACTERS.contains(c) {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .take(max)
+            .collect()
+    };
+
+    let mut short_name = format!("{}~1", convert(base, 6));
+    let extension = convert(extension, 3);
+    if !extension.is_empty() {
+        short_name.push('.');
+        short_name.push_str(&extension);
+    }
+    short_name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::short_name;
+
+    #[test]
+    fn test_short_name() {
+        assert_eq!(short_name("Windows"), "Windows");
+        assert_eq!(short_name("v1.0"), "v1.0");
+        assert_eq!(short_name("notepad.exe"), "notepad.exe");
+        assert_eq!(short_name("Program Files"), "PROGRA~1");
+        assert_eq!(short_name("WindowsPowerShell"), "WINDOW~1");
+        assert_eq!(short_name("powershell.exe"), "POWERS~1.EXE");
+        assert_eq!(short_name("archive.tar.gz"), "ARCHIV~1.GZ");
+        assert_eq!(short_name(".gitignore"), "GITIGN~1");
+        assert_eq!(short_name("a+b.text"), "A_B~1.TEX");
+    }
+}
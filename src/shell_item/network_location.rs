@@ -0,0 +1,51 @@
+use binrw::BinRead;
+use encoding_rs::Encoding;
+use getset::Getters;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::strings::{NullTerminatedString, StringEncoding};
+
+/// A network location shell item (class type indicator 0x40 - 0x4F)
+/// identifies a network resource, like a server or a share.
+#[derive(Clone, Debug, BinRead, Getters)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[br(import(default_codepage: &'static Encoding), assert(class_type & 0xf0 == 0x40))]
+#[getset(get = "pub")]
+#[allow(unused)]
+pub struct NetworkLocationShellItem {
+    /// The class type indicator, which specifies the type of the network
+    /// resource
+    class_type: u8,
+
+    #[getset(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    unknown: u8,
+
+    /// Flags which specify which of the optional strings are present
+    flags: u8,
+
+    /// The location of the network resource, for example `\\server\share`
+    #[br(
+        args(StringEncoding::CodePage(default_codepage)),
+        map=|n: NullTerminatedString| n.to_string()
+    )]
+    location: String,
+
+    /// An optional description of the network resource
+    #[br(
+        if(flags & 0x80 == 0x80),
+        args(StringEncoding::CodePage(default_codepage)),
+        map=|o: Option<NullTerminatedString>| o.map(|n| n.to_string())
+    )]
+    description: Option<String>,
+
+    /// Optional comments on the network resource
+    #[br(
+        if(flags & 0x40 == 0x40),
+        args(StringEncoding::CodePage(default_codepage)),
+        map=|o: Option<NullTerminatedString>| o.map(|n| n.to_string())
+    )]
+    comments: Option<String>,
+}
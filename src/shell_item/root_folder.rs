@@ -0,0 +1,38 @@
+use binrw::BinRead;
+use getset::Getters;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::Guid;
+
+/// The class identifier of the "My Computer" shell folder
+pub const MY_COMPUTER_CLSID: uuid::Uuid = uuid::uuid!("20D04FE0-3AEA-1069-A2D8-08002B30309D");
+
+/// A root folder shell item (class type indicator 0x1F) identifies a shell
+/// folder, such as "My Computer" or "Network", by its class identifier.
+#[derive(Clone, Debug, BinRead, Getters)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[br(assert(class_type == 0x1f))]
+#[getset(get = "pub")]
+#[allow(unused)]
+pub struct RootFolderShellItem {
+    /// The class type indicator, which is always 0x1F
+    class_type: u8,
+
+    /// A value which specifies the sort order of the shell folder
+    sort_index: u8,
+
+    /// The class identifier (CLSID) of the shell folder
+    shell_folder_id: Guid,
+}
+
+impl RootFolderShellItem {
+    /// encodes a root folder shell item for the shell folder `clsid`, without
+    /// the size of the ItemID
+    pub(crate) fn encode(sort_index: u8, clsid: uuid::Uuid) -> Vec<u8> {
+        let mut data = vec![0x1f, sort_index];
+        data.extend_from_slice(&clsid.to_bytes_le());
+        data
+    }
+}
@@ -0,0 +1,46 @@
+use binrw::BinRead;
+use encoding_rs::Encoding;
+use getset::Getters;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::strings::{NullTerminatedString, StringEncoding};
+
+/// A URI shell item (class type indicator 0x61) identifies a resource by its
+/// URI, for example an FTP or HTTP location.
+#[derive(Clone, Debug, BinRead, Getters)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[br(import(default_codepage: &'static Encoding), assert(class_type == 0x61))]
+#[getset(get = "pub")]
+#[allow(unused)]
+pub struct UriShellItem {
+    /// The class type indicator, which is always 0x61
+    class_type: u8,
+
+    /// Flags which specify how the URI is stored. If the bit 0x80 is set,
+    /// the URI is stored as Unicode string
+    flags: u8,
+
+    /// The size of the additional data, which precede the URI
+    #[getset(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    data_size: u16,
+
+    /// Additional data which are stored with the URI, e.g. connection
+    /// timestamps and credentials of FTP locations
+    #[br(count = data_size)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    data: Vec<u8>,
+
+    /// The URI of the resource
+    #[br(
+        args(if flags & 0x80 == 0x80 {
+            StringEncoding::Unicode
+        } else {
+            StringEncoding::CodePage(default_codepage)
+        }),
+        map=|n: NullTerminatedString| n.to_string()
+    )]
+    uri: String,
+}
@@ -0,0 +1,41 @@
+use binrw::BinRead;
+use encoding_rs::Encoding;
+use getset::Getters;
+
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::strings::{NullTerminatedString, StringEncoding};
+
+/// A volume shell item (class type indicator 0x20 - 0x2F) identifies a drive,
+/// usually by its drive letter.
+#[derive(Clone, Debug, BinRead, Getters)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[br(import(default_codepage: &'static Encoding), assert(class_type & 0xf0 == 0x20))]
+#[getset(get = "pub")]
+#[allow(unused)]
+pub struct VolumeShellItem {
+    /// The class type indicator. If the bit 0x01 is set, the item contains
+    /// the name of the volume.
+    class_type: u8,
+
+    /// The name of the volume, for example `C:\`
+    #[br(
+        if(class_type & 0x01 == 0x01),
+        args(StringEncoding::CodePage(default_codepage)),
+        map=|o: Option<NullTerminatedString>| o.map(|n| n.to_string())
+    )]
+    name: Option<String>,
+}
+
+impl VolumeShellItem {
+    /// encodes a volume shell item for the drive `drive_letter`, without the
+    /// size of the ItemID. Like Windows does, the name is padded with zeros
+    /// to a size of 22 bytes.
+    pub(crate) fn encode(drive_letter: char) -> Vec<u8> {
+        let mut data = vec![0x2f];
+        data.extend_from_slice(format!("{}:\\", drive_letter.to_ascii_uppercase()).as_bytes());
+        data.resize(23, 0);
+        data
+    }
+}
@@ -1,10 +1,13 @@
 use std::fmt::Display;
 
 use crate::{
+    parse_options::ParseContext,
     strings::{SizedString, StringEncoding},
     LinkFlags,
 };
 use binrw::BinRead;
+#[cfg(feature = "binwrite")]
+use binrw::BinWrite;
 use encoding_rs::Encoding;
 use getset::{Getters, Setters};
 use serde::Serialize;
@@ -15,15 +18,15 @@ use serde::Serialize;
 /// (section 2.1).
 #[derive(BinRead, Default, Getters, Setters, Debug, Serialize)]
 #[getset(get = "pub", set = "pub")]
-#[br(import(link_flags: LinkFlags, default_codepage: &'static Encoding))]
+#[br(import(link_flags: LinkFlags, default_codepage: &'static Encoding, ctx: &ParseContext))]
 pub struct StringData {
     /// NAME_STRING: An optional structure that specifies a description of the
     /// shortcut that is displayed to end users to identify the purpose of the
     /// shell link. This structure MUST be present if the HasName flag is set.
     #[br(
         if(link_flags & LinkFlags::HAS_NAME == LinkFlags::HAS_NAME),
-        args(StringEncoding::from(link_flags, default_codepage)),
-        map=|s: Option<SizedString>|s.map(|t| t.to_string())
+        parse_with = read_string,
+        args(StringEncoding::from(link_flags, default_codepage), ctx, "StringData/NameString"),
     )]
     name_string: Option<String>,
 
@@ -33,8 +36,8 @@ pub struct StringData {
     /// structure MUST be present if the HasRelativePath flag is set.
     #[br(
         if(link_flags & LinkFlags::HAS_RELATIVE_PATH == LinkFlags::HAS_RELATIVE_PATH),
-        args(StringEncoding::from(link_flags, default_codepage)),
-        map=|s: Option<SizedString>|s.map(|t| t.to_string())
+        parse_with = read_string,
+        args(StringEncoding::from(link_flags, default_codepage), ctx, "StringData/RelativePath"),
     )]
     relative_path: Option<String>,
 
@@ -43,8 +46,8 @@ pub struct StringData {
     /// This structure MUST be present if the HasWorkingDir flag is set.
     #[br(
         if(link_flags & LinkFlags::HAS_WORKING_DIR == LinkFlags::HAS_WORKING_DIR),
-        args(StringEncoding::from(link_flags, default_codepage)),
-        map=|s: Option<SizedString>|s.map(|t| t.to_string())
+        parse_with = read_string,
+        args(StringEncoding::from(link_flags, default_codepage), ctx, "StringData/WorkingDir"),
     )]
     working_dir: Option<String>,
 
@@ -53,8 +56,8 @@ pub struct StringData {
     /// target. This structure MUST be present if the HasArguments flag is set.
     #[br(
         if(link_flags & LinkFlags::HAS_ARGUMENTS == LinkFlags::HAS_ARGUMENTS),
-        args(StringEncoding::from(link_flags, default_codepage)),
-        map=|s: Option<SizedString>|s.map(|t| t.to_string())
+        parse_with = read_string,
+        args(StringEncoding::from(link_flags, default_codepage), ctx, "StringData/CommandLineArguments"),
     )]
     command_line_arguments: Option<String>,
 
@@ -63,12 +66,85 @@ pub struct StringData {
     /// structure MUST be present if the HasIconLocation flag is set.
     #[br(
         if(link_flags & LinkFlags::HAS_ICON_LOCATION == LinkFlags::HAS_ICON_LOCATION),
-        args(StringEncoding::from(link_flags, default_codepage)),
-        map=|s: Option<SizedString>|s.map(|t| t.to_string())
+        parse_with = read_string,
+        args(StringEncoding::from(link_flags, default_codepage), ctx, "StringData/IconLocation"),
     )]
     icon_location: Option<String>,
 }
 
+/// reads a string, whose length must not exceed
+/// [`ParseLimits::max_string_length`](crate::ParseLimits::max_string_length),
+/// and records its location as `path`
+fn read_string<R: std::io::Read + std::io::Seek>(
+    reader: &mut R,
+    endian: binrw::Endian,
+    (encoding, ctx, path): (StringEncoding, &ParseContext, &'static str),
+) -> binrw::BinResult<Option<String>> {
+    let pos = reader.stream_position()?;
+    let count_characters = u16::read_options(reader, endian, ())?;
+    ctx.check_limit(
+        "CountCharacters",
+        usize::from(count_characters),
+        ctx.limits().max_string_length,
+        pos,
+    )?;
+    reader.seek(std::io::SeekFrom::Start(pos))?;
+    let string = SizedString::read_options(reader, endian, (encoding,))?;
+    ctx.record_location(path, pos, reader.stream_position()?);
+    Ok(Some(string.to_string()))
+}
+
+#[cfg(feature = "binwrite")]
+impl BinWrite for StringData {
+    type Args<'a> = (LinkFlags, &'static Encoding);
+
+    fn write_options<W: std::io::Write + std::io::Seek>(
+        &self,
+        writer: &mut W,
+        endian: binrw::Endian,
+        args: Self::Args<'_>,
+    ) -> binrw::BinResult<()> {
+        let (link_flags, default_codepage) = args;
+        let encoding = StringEncoding::from(link_flags, default_codepage);
+
+        for (flag, value, name) in [
+            (LinkFlags::HAS_NAME, &self.name_string, "NAME_STRING"),
+            (
+                LinkFlags::HAS_RELATIVE_PATH,
+                &self.relative_path,
+                "RELATIVE_PATH",
+            ),
+            (LinkFlags::HAS_WORKING_DIR, &self.working_dir, "WORKING_DIR"),
+            (
+                LinkFlags::HAS_ARGUMENTS,
+                &self.command_line_arguments,
+                "COMMAND_LINE_ARGUMENTS",
+            ),
+            (
+                LinkFlags::HAS_ICON_LOCATION,
+                &self.icon_location,
+                "ICON_LOCATION",
+            ),
+        ] {
+            if link_flags & flag != flag {
+                continue;
+            }
+            match value {
+                Some(value) => {
+                    SizedString::from(value.clone()).write_options(writer, endian, (encoding,))?
+                }
+                None => {
+                    return Err(binrw::Error::AssertFail {
+                        pos: writer.stream_position()?,
+                        message: format!("{flag:?} is set, but {name} is missing"),
+                    })
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 impl Display for StringData {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut parts = Vec::new();
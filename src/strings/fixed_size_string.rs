@@ -1,4 +1,6 @@
 use binrw::BinRead;
+#[cfg(feature = "binwrite")]
+use binrw::BinWrite;
 use core::fmt::Display;
 use encoding_rs::Encoding;
 use substring::Substring;
@@ -30,13 +32,56 @@ impl BinRead for FixedSizeString {
             });
         }
         let mut res = cow.to_string();
-        if let Some(last_character) = res.find('\u{0000}') {
+        if let Some(last_character) = res.chars().position(|c| c == '\u{0000}') {
             res = res.substring(0, last_character).to_string();
         }
         Ok(Self(res))
     }
 }
 
+/// writes the string into a buffer of the given size, which is padded with
+/// NULL bytes
+#[cfg(feature = "binwrite")]
+impl BinWrite for FixedSizeString {
+    type Args<'a> = (usize, &'static Encoding);
+
+    fn write_options<W: std::io::Write + std::io::Seek>(
+        &self,
+        writer: &mut W,
+        _endian: binrw::Endian,
+        args: Self::Args<'_>,
+    ) -> binrw::BinResult<()> {
+        let (count, encoding) = args;
+        let pos = writer.stream_position()?;
+
+        // encoding_rs cannot encode to UTF-16
+        let string_encoding = if encoding == encoding_rs::UTF_16LE {
+            crate::StringEncoding::Unicode
+        } else {
+            crate::StringEncoding::CodePage(encoding)
+        };
+        let mut buffer = string_encoding.encode(&self.0, pos)?;
+        if buffer.len() > count {
+            return Err(binrw::error::Error::AssertFail {
+                pos,
+                message: format!(
+                    "String '{}' does not fit into a buffer of {count} bytes",
+                    self.0
+                ),
+            });
+        }
+        buffer.resize(count, 0);
+        writer.write_all(&buffer)?;
+        Ok(())
+    }
+}
+
+impl From<String> for FixedSizeString {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
 impl Display for FixedSizeString {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.0.fmt(f)
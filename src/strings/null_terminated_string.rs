@@ -1,3 +1,5 @@
+#[cfg(feature = "binwrite")]
+use binrw::BinWrite;
 use binrw::{BinRead, BinReaderExt, NullWideString};
 use core::fmt::Display;
 
@@ -45,6 +47,32 @@ impl BinRead for NullTerminatedString {
     }
 }
 
+#[cfg(feature = "binwrite")]
+impl BinWrite for NullTerminatedString {
+    type Args<'a> = (StringEncoding,);
+
+    fn write_options<W: std::io::Write + std::io::Seek>(
+        &self,
+        writer: &mut W,
+        _endian: binrw::Endian,
+        args: Self::Args<'_>,
+    ) -> binrw::BinResult<()> {
+        let mut buffer = args.0.encode(&self.0, writer.stream_position()?)?;
+        match args.0 {
+            StringEncoding::CodePage(_) => buffer.push(0),
+            StringEncoding::Unicode => buffer.extend_from_slice(&[0, 0]),
+        }
+        writer.write_all(&buffer)?;
+        Ok(())
+    }
+}
+
+impl From<String> for NullTerminatedString {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
 impl Display for NullTerminatedString {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.0.fmt(f)
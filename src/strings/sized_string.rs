@@ -1,6 +1,8 @@
 use std::fmt::Display;
 
 use binrw::{BinRead, BinReaderExt};
+#[cfg(feature = "binwrite")]
+use binrw::{BinWrite, BinWriterExt};
 use encoding_rs::UTF_16LE;
 use log::trace;
 
@@ -42,7 +44,7 @@ impl BinRead for SizedString {
                 Ok(Self(cow.to_string()))
             }
             StringEncoding::Unicode => {
-                let mut buffer = vec![0; (count_characters * 2).into()];
+                let mut buffer = vec![0; usize::from(count_characters) * 2];
                 reader.read_exact(&mut buffer)?;
                 let (cow, _, had_errors) = UTF_16LE.decode(&buffer);
                 if had_errors {
@@ -59,6 +61,39 @@ impl BinRead for SizedString {
     }
 }
 
+#[cfg(feature = "binwrite")]
+impl BinWrite for SizedString {
+    type Args<'a> = (StringEncoding,);
+
+    fn write_options<W: std::io::Write + std::io::Seek>(
+        &self,
+        writer: &mut W,
+        _endian: binrw::Endian,
+        args: Self::Args<'_>,
+    ) -> binrw::BinResult<()> {
+        let pos = writer.stream_position()?;
+        let buffer = args.0.encode(&self.0, pos)?;
+        let count_characters = match args.0 {
+            StringEncoding::CodePage(_) => buffer.len(),
+            StringEncoding::Unicode => buffer.len() / 2,
+        };
+        let count_characters =
+            u16::try_from(count_characters).map_err(|_| binrw::Error::AssertFail {
+                pos,
+                message: format!("string of {count_characters} characters is too long"),
+            })?;
+        writer.write_le(&count_characters)?;
+        writer.write_all(&buffer)?;
+        Ok(())
+    }
+}
+
+impl From<String> for SizedString {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
 impl Display for SizedString {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.0.fmt(f)
@@ -22,4 +22,26 @@ impl StringEncoding {
             Self::CodePage(default_codepage)
         }
     }
+
+    /// encodes `value` using this encoding. `pos` is only used for error
+    /// reporting.
+    #[cfg(feature = "binwrite")]
+    pub(crate) fn encode(&self, value: &str, pos: u64) -> binrw::BinResult<Vec<u8>> {
+        match self {
+            Self::CodePage(codepage) => {
+                let (cow, _, had_errors) = codepage.encode(value);
+                if had_errors {
+                    return Err(binrw::Error::AssertFail {
+                        pos,
+                        message: format!(
+                            "unable to encode String '{value}' to {}",
+                            codepage.name()
+                        ),
+                    });
+                }
+                Ok(cow.into_owned())
+            }
+            Self::Unicode => Ok(value.encode_utf16().flat_map(u16::to_le_bytes).collect()),
+        }
+    }
 }
@@ -0,0 +1,48 @@
+use getset::Getters;
+#[cfg(feature = "serde")]
+use serde::{ser::SerializeStruct, Serialize};
+
+/// Data which follow the TerminalBlock of the ExtraData section. They are
+/// not part of the shell link and are ignored by Windows, which is why they
+/// can be used to hide a payload in an otherwise valid shell link.
+#[derive(Clone, Debug, Eq, PartialEq, Getters)]
+#[getset(get = "pub")]
+pub struct TrailingData {
+    /// The absolute offset, in bytes, where the data start
+    offset: u64,
+
+    /// The raw data
+    data: Vec<u8>,
+}
+
+impl TrailingData {
+    pub(crate) fn new(offset: u64, data: Vec<u8>) -> Self {
+        Self { offset, data }
+    }
+
+    /// returns the number of bytes
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// returns `true` if there are no data
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
+/// serializes the offset, the length and the data as hex string
+#[cfg(feature = "serde")]
+impl Serialize for TrailingData {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let hex: String = self.data.iter().map(|b| format!("{b:02x}")).collect();
+        let mut s = serializer.serialize_struct("TrailingData", 3)?;
+        s.serialize_field("offset", &self.offset)?;
+        s.serialize_field("length", &self.data.len())?;
+        s.serialize_field("data", &hex)?;
+        s.end()
+    }
+}
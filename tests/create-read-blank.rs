@@ -2,7 +2,7 @@ use log::info;
 
 use std::fs;
 
-const TEST_FILE_NAME: &'static str = "temp.lnk";
+const TEST_FILE_NAME: &str = "temp.lnk";
 
 #[test]
 fn create_read_blank() {
@@ -0,0 +1,267 @@
+use encoding_rs::WINDOWS_1252;
+use lnk::linkinfo::{CommonNetworkRelativeLink, NetworkProviderType};
+use lnk::{Error, HotkeyFlags, HotkeyKey, HotkeyModifiers, LinkInfo, ParseOptions, ShellLink};
+
+const TEST_FILE_NAME: &str = "tests/test.lnk";
+
+#[test]
+fn save_read_existing_link() {
+    let _ = pretty_env_logger::try_init();
+
+    let original = ShellLink::open(TEST_FILE_NAME, WINDOWS_1252).unwrap();
+    let temp_file = std::env::temp_dir().join("lnk-save-read.lnk");
+    original.save(&temp_file).expect("Failed to save shortcut!");
+
+    let saved = ShellLink::open(&temp_file, WINDOWS_1252).unwrap();
+    std::fs::remove_file(&temp_file).expect("delete shortcut");
+
+    assert_eq!(saved.header().link_flags(), original.header().link_flags());
+    assert_eq!(saved.link_target(), original.link_target());
+    assert_eq!(
+        saved.linktarget_id_list().as_ref().unwrap().id_list().len(),
+        original
+            .linktarget_id_list()
+            .as_ref()
+            .unwrap()
+            .id_list()
+            .len()
+    );
+    assert_eq!(
+        saved.string_data().relative_path(),
+        original.string_data().relative_path()
+    );
+    assert_eq!(
+        saved.string_data().working_dir(),
+        original.string_data().working_dir()
+    );
+}
+
+#[test]
+fn save_read_extra_data() {
+    let _ = pretty_env_logger::try_init();
+
+    let original = ShellLink::open("tests/Windows PowerShell.lnk", WINDOWS_1252).unwrap();
+    let temp_file = std::env::temp_dir().join("lnk-save-read-extra-data.lnk");
+    original.save(&temp_file).expect("Failed to save shortcut!");
+
+    let saved = ShellLink::open(&temp_file, WINDOWS_1252).unwrap();
+    std::fs::remove_file(&temp_file).expect("delete shortcut");
+
+    assert_eq!(
+        format!("{:?}", saved.extra_data()),
+        format!("{:?}", original.extra_data())
+    );
+}
+
+#[test]
+fn to_bytes_from_bytes() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut shortcut = ShellLink::default();
+    shortcut.set_arguments(Some("--help".to_string()));
+    let data = shortcut.to_bytes().unwrap();
+
+    let parsed = ShellLink::from_bytes(&data, WINDOWS_1252).unwrap();
+    assert_eq!(
+        parsed.string_data().command_line_arguments(),
+        &Some("--help".to_string())
+    );
+    assert_eq!(parsed.to_bytes().unwrap(), data);
+}
+
+#[test]
+fn save_read_unknown_extra_data_block() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    data.truncate(data.len() - 4);
+    data.extend_from_slice(&12u32.to_le_bytes());
+    data.extend_from_slice(&0x12345678u32.to_le_bytes());
+    data.extend_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+    data.extend_from_slice(&0u32.to_le_bytes());
+
+    let shortcut = ShellLink::from_bytes(&data, WINDOWS_1252).unwrap();
+    let saved = shortcut.to_bytes().unwrap();
+    assert_eq!(&saved[saved.len() - 16..], &data[data.len() - 16..]);
+}
+
+#[test]
+fn save_read_byte_exact() {
+    let _ = pretty_env_logger::try_init();
+
+    for file_name in ["tests/test.lnk", "tests/Windows PowerShell.lnk"] {
+        let original = std::fs::read(file_name).unwrap();
+        let link = ShellLink::from_bytes(&original, WINDOWS_1252).unwrap();
+        assert_eq!(link.to_bytes().unwrap(), original, "{file_name}");
+    }
+}
+
+#[test]
+fn save_read_byte_exact_invalid_sample() {
+    let _ = pretty_env_logger::try_init();
+
+    // np.lnk cannot be part of the byte-exact round trip: its LinkCLSID is
+    // corrupted (0x0c instead of 0xc0 at offset 12), and the LinkInfo,
+    // RelativePath and WorkingDir which are announced by its LinkFlags are
+    // missing. It is rejected instead of being written differently.
+    let original = std::fs::read("np.lnk").unwrap();
+    assert_eq!(original.len(), 0x4c);
+    for options in [ParseOptions::default(), ParseOptions::lenient()] {
+        assert!(matches!(
+            ShellLink::from_bytes_with_options(&original, WINDOWS_1252, options),
+            Err(Error::NotAShellLinkError)
+        ));
+    }
+}
+
+#[test]
+fn save_read_byte_exact_with_trailing_data() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut original = std::fs::read("tests/test.lnk").unwrap();
+    original.extend_from_slice(b"trailing data");
+    let mut link = ShellLink::from_bytes(&original, WINDOWS_1252).unwrap();
+
+    // trailing data are stripped, unless they are explicitly kept
+    assert_eq!(
+        link.to_bytes().unwrap(),
+        &original[..original.len() - b"trailing data".len()]
+    );
+    link.set_keep_trailing_data(true);
+    assert_eq!(link.to_bytes().unwrap(), original);
+}
+
+#[test]
+fn save_read_built_link() {
+    let _ = pretty_env_logger::try_init();
+
+    let link = ShellLink::builder()
+        .target(r"C:\Windows\System32\cmd.exe")
+        .environment_target(r"%windir%\System32\cmd.exe")
+        .arguments("/k ver")
+        .hotkey(HotkeyFlags::new(
+            HotkeyKey::KeyC,
+            HotkeyModifiers::HOTKEYF_CONTROL,
+        ))
+        .console(lnk::extradata::console_data::ConsoleDataBlock::default())
+        .build()
+        .unwrap();
+    let data = link.to_bytes().unwrap();
+
+    let parsed = ShellLink::from_bytes(&data, WINDOWS_1252).unwrap();
+    assert!(parsed.warnings().is_empty());
+    assert_eq!(
+        parsed.link_target().as_deref(),
+        Some(r"C:\Windows\System32\cmd.exe")
+    );
+    assert_eq!(parsed.header().link_flags(), link.header().link_flags());
+    assert_eq!(
+        parsed.id_list_target().as_deref(),
+        Some(r"C:\Windows\System32\cmd.exe")
+    );
+    assert_eq!(
+        parsed.string_data().command_line_arguments(),
+        &Some("/k ver".to_string())
+    );
+    assert_eq!(*parsed.header().hotkey().key(), HotkeyKey::KeyC);
+    assert_eq!(
+        format!("{:?}", parsed.extra_data()),
+        format!("{:?}", link.extra_data())
+    );
+}
+
+#[test]
+fn save_read_link_for_windows_path() {
+    let _ = pretty_env_logger::try_init();
+
+    let link = ShellLink::new_for_windows_path(r"C:\Program Files\App\app.exe", None).unwrap();
+    let data = link.to_bytes().unwrap();
+
+    let parsed = ShellLink::from_bytes(&data, WINDOWS_1252).unwrap();
+    assert!(parsed.warnings().is_empty());
+    assert_eq!(
+        parsed.link_target().as_deref(),
+        Some(r"C:\Program Files\App\app.exe")
+    );
+    assert_eq!(
+        parsed.id_list_target().as_deref(),
+        Some(r"C:\Program Files\App\app.exe")
+    );
+    assert_eq!(parsed.to_bytes().unwrap(), data);
+}
+
+#[test]
+fn save_read_network_link() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut network_link =
+        CommonNetworkRelativeLink::new(r"\\fileserver\?????????", Some(NetworkProviderType::Smb));
+    network_link.set_net_name_unicode(Some(r"\\fileserver\Документы".to_string()));
+    network_link.set_device_name(Some("Z:".to_string()));
+    let mut link = ShellLink::default();
+    link.set_link_info(Some(LinkInfo::new_network(
+        network_link,
+        r"reports\2024.xlsx",
+        None,
+    )));
+    let data = link.to_bytes().unwrap();
+
+    let parsed = ShellLink::from_bytes(&data, WINDOWS_1252).unwrap();
+    assert!(parsed.warnings().is_empty());
+    assert_eq!(
+        parsed.link_target().as_deref(),
+        Some(r"Z:\reports\2024.xlsx")
+    );
+    let network_link = parsed
+        .link_info()
+        .as_ref()
+        .and_then(|info| info.common_network_relative_link().as_ref())
+        .unwrap();
+    assert_eq!(
+        network_link.net_name_unicode().as_deref(),
+        Some(r"\\fileserver\Документы")
+    );
+    assert_eq!(network_link.device_name().as_deref(), Some("Z:"));
+    assert_eq!(
+        *network_link.network_provider_type(),
+        Some(NetworkProviderType::Smb)
+    );
+}
+
+#[test]
+fn save_read_built_link_with_environment_target() {
+    let _ = pretty_env_logger::try_init();
+
+    let target = r"%USERPROFILE%\Рабочий стол\x.exe";
+    let link = ShellLink::builder()
+        .environment_target(target)
+        .build()
+        .unwrap();
+    let data = link.to_bytes().unwrap();
+
+    let parsed = ShellLink::from_bytes(&data, WINDOWS_1252).unwrap();
+    assert!(parsed.warnings().is_empty());
+    assert_eq!(
+        format!("{:?}", parsed.extra_data()),
+        format!("{:?}", link.extra_data())
+    );
+}
+
+#[test]
+fn save_read_longest_strings() {
+    let _ = pretty_env_logger::try_init();
+
+    let arguments = "x".repeat(u16::MAX as usize);
+    let link = ShellLink::builder()
+        .target(r"C:\Windows\notepad.exe")
+        .arguments(arguments.clone())
+        .build()
+        .unwrap();
+    let data = link.to_bytes().unwrap();
+
+    let parsed = ShellLink::from_bytes(&data, WINDOWS_1252).unwrap();
+    assert_eq!(
+        parsed.string_data().command_line_arguments(),
+        &Some(arguments)
+    );
+}
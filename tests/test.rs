@@ -87,3 +87,1043 @@ fn test_no_panic_reading_other_filetypes() {
     // Shouldn't have panicked by now!
     assert!(res.is_err());
 }
+
+#[test]
+fn test_from_bytes() {
+    let _ = pretty_env_logger::try_init();
+
+    let data = std::fs::read(TEST_FILE_NAME).unwrap();
+    let shortcut = ShellLink::from_bytes(&data, WINDOWS_1252).unwrap();
+    assert_eq!(
+        shortcut.string_data().working_dir(),
+        &Some(r"C:\test".to_string())
+    );
+    assert_eq!(
+        shortcut.link_info().as_ref().unwrap().local_base_path(),
+        Some(r"C:\test\a.txt")
+    );
+
+    // parsing starts at the current position of the reader
+    let mut prefixed = vec![0xff; 16];
+    prefixed.extend_from_slice(&data);
+    let mut reader = std::io::Cursor::new(prefixed);
+    reader.set_position(16);
+    let shortcut = ShellLink::from_reader(&mut reader, WINDOWS_1252).unwrap();
+    assert_eq!(
+        shortcut.string_data().relative_path(),
+        &Some(r".\a.txt".to_string())
+    );
+}
+
+#[test]
+fn test_shell_items() {
+    let _ = pretty_env_logger::try_init();
+
+    let shortcut = ShellLink::open(TEST_FILE_NAME, WINDOWS_1252).unwrap();
+    let id_list = shortcut.linktarget_id_list().as_ref().unwrap().id_list();
+    assert_eq!(id_list.len(), 4);
+
+    match id_list[0].shell_item() {
+        ShellItem::RootFolder(item) => assert_eq!(
+            item.shell_folder_id().to_string(),
+            "20d04fe0-3aea-1069-a2d8-08002b30309d"
+        ),
+        other => panic!("expected a root folder, found {other:?}"),
+    }
+    match id_list[1].shell_item() {
+        ShellItem::Volume(item) => assert_eq!(item.name(), &Some(r"C:\".to_string())),
+        other => panic!("expected a volume, found {other:?}"),
+    }
+    match id_list[2].shell_item() {
+        ShellItem::FileEntry(item) => {
+            assert!(item.is_directory());
+            assert_eq!(item.primary_name(), "test");
+        }
+        other => panic!("expected a file entry, found {other:?}"),
+    }
+    match id_list[3].shell_item() {
+        ShellItem::FileEntry(item) => {
+            assert!(item.is_file());
+            assert_eq!(item.primary_name(), "a.txt");
+            assert_eq!(item.long_name(), Some("a.txt"));
+            assert_eq!(item.mft_entry(), Some(28205));
+            assert_eq!(item.mft_sequence(), Some(406));
+            assert_eq!(
+                item.creation_time().and_then(|t| t.datetime()),
+                NaiveDate::from_ymd_opt(2008, 9, 12)
+                    .unwrap()
+                    .and_hms_opt(20, 27, 18)
+            );
+            assert_eq!(
+                *item.file_attributes(),
+                FileAttributeFlags::FILE_ATTRIBUTE_ARCHIVE
+            );
+            assert_eq!(
+                item.modification_time().datetime().unwrap().date(),
+                NaiveDate::from_ymd_opt(2008, 9, 12).unwrap()
+            );
+        }
+        other => panic!("expected a file entry, found {other:?}"),
+    }
+}
+
+#[test]
+fn test_localized_name_codepage() {
+    let _ = pretty_env_logger::try_init();
+
+    // file entry shell item of a file, with the primary name "A.TXT"
+    let mut data = vec![0x32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x20, 0];
+    data.extend_from_slice(b"A.TXT\0");
+
+    // version 3 extension block, with the localized name stored as ANSI
+    let long_name: Vec<u8> = "a.txt"
+        .encode_utf16()
+        .chain([0])
+        .flat_map(u16::to_le_bytes)
+        .collect();
+    let (localized_name, _, _) = encoding_rs::SHIFT_JIS.encode("文書.txt");
+    let size = 20 + long_name.len() + localized_name.len() + 1 + 2;
+    data.extend_from_slice(&(size as u16).to_le_bytes());
+    data.extend_from_slice(&3u16.to_le_bytes());
+    data.extend_from_slice(&0xbeef0004u32.to_le_bytes());
+    data.extend_from_slice(&[0; 8]);
+    data.extend_from_slice(&0x14u16.to_le_bytes());
+    data.extend_from_slice(&(localized_name.len() as u16 + 1).to_le_bytes());
+    data.extend_from_slice(&long_name);
+    data.extend_from_slice(&localized_name);
+    data.extend_from_slice(&[0, 0x0c, 0]);
+
+    match ShellItem::parse(&data, encoding_rs::SHIFT_JIS) {
+        ShellItem::FileEntry(item) => {
+            let extension = item.file_entry_extension().unwrap();
+            assert_eq!(extension.long_name().as_deref(), Some("a.txt"));
+            assert_eq!(extension.localized_name().as_deref(), Some("文書.txt"));
+        }
+        other => panic!("expected a file entry, found {other:?}"),
+    }
+}
+
+#[test]
+fn test_link_target_with_empty_suffix() {
+    let _ = pretty_env_logger::try_init();
+
+    // Windows stores the complete path of local targets as LocalBasePath,
+    // followed by an empty CommonPathSuffix
+    let shortcut = ShellLink::open(TEST_FILE_NAME, WINDOWS_1252).unwrap();
+    let link_info = shortcut.link_info().as_ref().unwrap();
+    assert_eq!(link_info.local_base_path(), Some(r"C:\test\a.txt"));
+    assert_eq!(link_info.common_path_suffix(), "");
+
+    // formerly, the separator was always inserted
+    let old_link_target = format!(
+        "{}\\{}",
+        link_info.local_base_path().unwrap(),
+        link_info.common_path_suffix()
+    );
+    assert_eq!(old_link_target, r"C:\test\a.txt\");
+    assert_eq!(shortcut.link_target().as_deref(), Some(r"C:\test\a.txt"));
+}
+
+#[test]
+fn test_id_list_with_early_terminal_id() {
+    let _ = pretty_env_logger::try_init();
+
+    // a TerminalID in place of the third ItemID
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    let original = ShellLink::from_bytes(&data, WINDOWS_1252).unwrap();
+    let item = original.location("LinkTargetIDList/ItemID[2]").unwrap();
+    let offset = *item.offset() as usize;
+    data[offset..offset + 2].copy_from_slice(&[0, 0]);
+
+    match ShellLink::from_bytes(&data, WINDOWS_1252) {
+        Err(Error::ParseError(error)) => {
+            assert_eq!(*error.section(), Section::LinkTargetIdList);
+            assert_eq!(*error.offset(), offset as u64);
+            assert_eq!(error.field().as_deref(), Some("TerminalID"));
+        }
+        other => panic!("expected a parse error, found {other:?}"),
+    }
+
+    // in lenient mode, the list ends at the TerminalID
+    let shortcut =
+        ShellLink::from_bytes_with_options(&data, WINDOWS_1252, ParseOptions::lenient()).unwrap();
+    assert_eq!(
+        shortcut
+            .linktarget_id_list()
+            .as_ref()
+            .unwrap()
+            .id_list()
+            .len(),
+        2
+    );
+    assert_eq!(shortcut.warnings().len(), 1);
+    assert_eq!(*shortcut.warnings()[0].offset(), offset as u64);
+    assert_eq!(shortcut.link_target(), original.link_target());
+}
+
+#[test]
+fn test_id_list_target() {
+    let _ = pretty_env_logger::try_init();
+
+    let shortcut = ShellLink::open(TEST_FILE_NAME, WINDOWS_1252).unwrap();
+    assert_eq!(
+        shortcut.id_list_target(),
+        Some(r"C:\test\a.txt".to_string())
+    );
+
+    let shortcut = ShellLink::open("tests/Windows PowerShell.lnk", WINDOWS_1252).unwrap();
+    assert_eq!(
+        shortcut.id_list_target(),
+        Some(r"C:\Windows\System32\WindowsPowerShell\v1.0\powershell.exe".to_string())
+    );
+
+    // items which cannot be decoded do not hide the rest of the path
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    let shortcut = ShellLink::from_bytes(&data, WINDOWS_1252).unwrap();
+    let directory = shortcut.location("LinkTargetIDList/ItemID[2]").unwrap();
+    data[*directory.offset() as usize + 2] = 0x00;
+    let shortcut = ShellLink::from_bytes(&data, WINDOWS_1252).unwrap();
+    assert_eq!(
+        shortcut.id_list_target(),
+        Some(r"C:\<unknown 0x00>\a.txt".to_string())
+    );
+}
+
+#[test]
+fn test_extra_data() {
+    let _ = pretty_env_logger::try_init();
+
+    let shortcut = ShellLink::open(TEST_FILE_NAME, WINDOWS_1252).unwrap();
+    let extra_data = shortcut.extra_data();
+    assert_eq!(extra_data.blocks().len(), 1);
+
+    let tracker = extra_data.tracker().unwrap();
+    assert_eq!(tracker.machine_id(), "chris-xps");
+    assert_eq!(tracker.mac_address().as_deref(), Some("00:13:72:16:87:4a"));
+    assert_eq!(tracker.clock_sequence(), Some(0x1499));
+    assert_eq!(
+        tracker.creation_time().unwrap().date(),
+        NaiveDate::from_ymd_opt(2008, 9, 10).unwrap()
+    );
+    assert_eq!(tracker.volume_id(), tracker.birth_volume_id());
+    assert!(extra_data.get(0xa0000003).is_some());
+    assert!(extra_data.environment_variables().is_none());
+}
+
+#[test]
+fn test_extra_data_link_flags() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut shortcut = ShellLink::open(TEST_FILE_NAME, WINDOWS_1252).unwrap();
+    let block = extradata::ExtraDataBlock::EnvironmentProps(
+        extradata::environment_variable_data::EnvironmentVariableDataBlock::new(
+            r"%windir%\notepad.exe",
+        ),
+    );
+    assert!(shortcut.insert_extra_data_block(block).is_none());
+    assert!(shortcut
+        .header()
+        .link_flags()
+        .contains(LinkFlags::HAS_EXP_STRING));
+    assert_eq!(
+        shortcut
+            .extra_data()
+            .environment_variables()
+            .unwrap()
+            .target_unicode()
+            .as_deref(),
+        Some(r"%windir%\notepad.exe")
+    );
+
+    assert!(shortcut.remove_extra_data_block(0xa0000001).is_some());
+    assert!(!shortcut
+        .header()
+        .link_flags()
+        .contains(LinkFlags::HAS_EXP_STRING));
+    assert!(shortcut.extra_data().environment_variables().is_none());
+    assert_eq!(shortcut.extra_data().blocks().len(), 1);
+}
+
+#[test]
+fn test_lenient_parsing() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    // invalid ShowCommand
+    data[0x3c] = 0x99;
+    // reserved value which must be zero
+    data[0x42] = 0x01;
+
+    assert!(ShellLink::from_bytes(&data, WINDOWS_1252).is_err());
+
+    let shortcut =
+        ShellLink::from_bytes_with_options(&data, WINDOWS_1252, ParseOptions::lenient()).unwrap();
+    assert_eq!(*shortcut.header().show_command(), ShowCommand::ShowNormal);
+    assert_eq!(
+        shortcut.link_target(),
+        ShellLink::open(TEST_FILE_NAME, WINDOWS_1252)
+            .unwrap()
+            .link_target()
+    );
+
+    let offsets: Vec<u64> = shortcut.warnings().iter().map(|w| *w.offset()).collect();
+    assert_eq!(offsets, vec![0x3c, 0x42]);
+
+    // undecodable blocks are reported with a single line
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    let tracker_start = data.len() - 4 - 0x60;
+    data[tracker_start + 12] = 0x01;
+    let shortcut =
+        ShellLink::from_bytes_with_options(&data, WINDOWS_1252, ParseOptions::lenient()).unwrap();
+    let messages: Vec<String> = shortcut
+        .warnings()
+        .iter()
+        .map(|w| w.message().clone())
+        .collect();
+    assert_eq!(
+        messages,
+        vec![format!(
+            "unable to decode ExtraData block 0xa0000003 at offset 0x{:08x} (field Version): \
+             invalid Version: expected 0, found 1",
+            tracker_start + 12
+        )]
+    );
+}
+
+/// returns the bytes of the test file, with an additional ExtraData block
+/// of unknown type
+fn test_file_with_unknown_block() -> Vec<u8> {
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    // remove the terminal block
+    data.truncate(data.len() - 4);
+    data.extend_from_slice(&12u32.to_le_bytes());
+    data.extend_from_slice(&0x12345678u32.to_le_bytes());
+    data.extend_from_slice(&[0xde, 0xad, 0xbe, 0xef]);
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data
+}
+
+#[test]
+fn test_unknown_extra_data_block() {
+    let _ = pretty_env_logger::try_init();
+
+    let shortcut = ShellLink::from_bytes(&test_file_with_unknown_block(), WINDOWS_1252).unwrap();
+    assert!(shortcut.extra_data().tracker().is_some());
+    match shortcut.extra_data().get(0x12345678) {
+        Some(extradata::ExtraDataBlock::Unknown { signature, data }) => {
+            assert_eq!(*signature, 0x12345678);
+            assert_eq!(data, &[0xde, 0xad, 0xbe, 0xef]);
+        }
+        other => panic!("expected an unknown block, found {other:?}"),
+    }
+
+    #[cfg(feature = "serde")]
+    {
+        let json = serde_json::to_value(shortcut.extra_data()).unwrap();
+        assert_eq!(json["blocks"][1]["Unknown"]["data"], "deadbeef");
+    }
+}
+
+#[test]
+fn test_parse_errors() {
+    let _ = pretty_env_logger::try_init();
+
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    data[0x3c] = 0x99;
+    match ShellLink::from_bytes(&data, WINDOWS_1252) {
+        Err(Error::ParseError(error)) => {
+            assert_eq!(*error.section(), Section::Header);
+            assert_eq!(*error.offset(), 0x3c);
+            assert_eq!(error.field().as_deref(), Some("ShowCommand"));
+            assert_eq!(error.actual().as_deref(), Some("0x99"));
+        }
+        other => panic!("expected a parse error, found {other:?}"),
+    }
+
+    // Version of the TrackerDataBlock, which is the last block
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    let tracker_start = data.len() - 4 - 0x60;
+    data[tracker_start + 12] = 0x01;
+    match ShellLink::from_bytes(&data, WINDOWS_1252) {
+        Err(Error::ParseError(error)) => {
+            assert_eq!(*error.section(), Section::ExtraDataBlock(0xa0000003));
+            assert_eq!(*error.offset(), tracker_start as u64 + 12);
+            assert_eq!(error.field().as_deref(), Some("Version"));
+            assert_eq!(error.expected().as_deref(), Some("0"));
+            assert_eq!(error.actual().as_deref(), Some("1"));
+        }
+        other => panic!("expected a parse error, found {other:?}"),
+    }
+
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    data[0x04] = 0x00;
+    assert!(matches!(
+        ShellLink::from_bytes(&data, WINDOWS_1252),
+        Err(Error::NotAShellLinkError)
+    ));
+}
+
+#[test]
+fn test_no_panic_on_corrupted_input() {
+    for file_name in [TEST_FILE_NAME, "tests/Windows PowerShell.lnk"] {
+        let original = std::fs::read(file_name).unwrap();
+
+        let mut samples = Vec::new();
+        for len in 0..original.len() {
+            samples.push(original[..len].to_vec());
+        }
+        for pos in 0..original.len() {
+            for value in [0x00, 0x7f, 0xff] {
+                let mut data = original.clone();
+                data[pos] = value;
+                samples.push(data);
+            }
+        }
+
+        for data in samples {
+            for options in [ParseOptions::default(), ParseOptions::lenient()] {
+                if let Ok(shortcut) =
+                    ShellLink::from_bytes_with_options(&data, WINDOWS_1252, options)
+                {
+                    let _ = shortcut.link_target();
+                    let _ = shortcut.id_list_target();
+                    let _ = format!("{shortcut:?}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_parse_limits() {
+    let _ = pretty_env_logger::try_init();
+
+    let data = std::fs::read(TEST_FILE_NAME).unwrap();
+
+    // exceeding a limit is an error in lenient mode, too
+    for options in [ParseOptions::default(), ParseOptions::lenient()] {
+        let parse_with = |limits: ParseLimits| {
+            ShellLink::from_bytes_with_options(
+                &data,
+                WINDOWS_1252,
+                ParseOptions {
+                    limits,
+                    ..options.clone()
+                },
+            )
+        };
+
+        // the default limits are suitable for regular files
+        assert!(parse_with(ParseLimits::default()).is_ok());
+        assert!(parse_with(ParseLimits {
+            max_size: data.len() as u64,
+            ..Default::default()
+        })
+        .is_ok());
+
+        let error = parse_with(ParseLimits {
+            max_size: data.len() as u64 - 1,
+            ..Default::default()
+        })
+        .unwrap_err();
+        assert!(error.to_string().contains("maximum size"), "{error}");
+
+        match parse_with(ParseLimits {
+            max_item_ids: 1,
+            ..Default::default()
+        }) {
+            Err(Error::ParseError(error)) => {
+                assert_eq!(*error.section(), Section::LinkTargetIdList);
+                assert_eq!(error.field().as_deref(), Some("ItemIDs"));
+            }
+            other => panic!("expected a parse error, found {other:?}"),
+        }
+
+        match parse_with(ParseLimits {
+            max_extra_data_blocks: 0,
+            ..Default::default()
+        }) {
+            Err(Error::ParseError(error)) => {
+                assert_eq!(*error.section(), Section::ExtraData);
+                assert_eq!(error.field().as_deref(), Some("ExtraDataBlocks"));
+            }
+            other => panic!("expected a parse error, found {other:?}"),
+        }
+
+        match parse_with(ParseLimits {
+            max_string_length: 4,
+            ..Default::default()
+        }) {
+            Err(Error::ParseError(error)) => {
+                assert_eq!(*error.section(), Section::StringData);
+                assert_eq!(error.field().as_deref(), Some("CountCharacters"));
+            }
+            other => panic!("expected a parse error, found {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn test_structure_locations() {
+    let _ = pretty_env_logger::try_init();
+
+    let shortcut = ShellLink::open(TEST_FILE_NAME, WINDOWS_1252).unwrap();
+    let location = |path: &str| {
+        let location = shortcut.location(path).unwrap();
+        (*location.offset(), *location.size())
+    };
+
+    assert_eq!(location("ShellLinkHeader"), (0, 0x4c));
+    assert_eq!(location("LinkTargetIDList"), (0x4c, 0xbf));
+    assert_eq!(location("LinkTargetIDList/ItemID[0]"), (0x4e, 0x14));
+    assert_eq!(location("LinkTargetIDList/ItemID[3]"), (0xc1, 0x48));
+    assert_eq!(location("LinkInfo"), (0x10b, 0x3c));
+    assert_eq!(location("LinkInfo/VolumeID"), (0x127, 0x11));
+    assert_eq!(location("LinkInfo/LocalBasePath"), (0x138, 0x0e));
+    assert_eq!(location("StringData/RelativePath"), (0x147, 0x10));
+    assert_eq!(location("ExtraData/Block[0]"), (0x167, 0x60));
+    assert!(shortcut.location("StringData/NameString").is_none());
+
+    let offsets: Vec<u64> = shortcut.locations().iter().map(|l| *l.offset()).collect();
+    assert!(offsets.windows(2).all(|w| w[0] <= w[1]));
+}
+
+#[test]
+fn test_trailing_data() {
+    let _ = pretty_env_logger::try_init();
+
+    let shortcut = ShellLink::open(TEST_FILE_NAME, WINDOWS_1252).unwrap();
+    assert!(shortcut.trailing_data().is_none());
+
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    let len = data.len();
+    data.extend_from_slice(b"MZ\x90\x00");
+    let shortcut = ShellLink::from_bytes(&data, WINDOWS_1252).unwrap();
+    let trailing_data = shortcut.trailing_data().unwrap();
+    assert_eq!(*trailing_data.offset(), len as u64);
+    assert_eq!(trailing_data.len(), 4);
+    assert_eq!(trailing_data.data(), b"MZ\x90\x00");
+
+    #[cfg(feature = "serde")]
+    {
+        let json = serde_json::to_value(&shortcut).unwrap();
+        assert_eq!(json["trailing_data"]["offset"], len);
+        assert_eq!(json["trailing_data"]["length"], 4);
+        assert_eq!(json["trailing_data"]["data"], "4d5a9000");
+    }
+}
+
+/// a reader whose positions are shifted by `base`, which simulates data at
+/// the end of a huge stream, like a disk image
+struct ShiftedReader<R> {
+    inner: R,
+    base: u64,
+}
+
+impl<R: std::io::Read> std::io::Read for ShiftedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl<R: std::io::Seek> std::io::Seek for ShiftedReader<R> {
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        let pos = match pos {
+            std::io::SeekFrom::Start(pos) => std::io::SeekFrom::Start(pos - self.base),
+            pos => pos,
+        };
+        Ok(self.inner.seek(pos)? + self.base)
+    }
+}
+
+#[test]
+fn test_link_beyond_4_gib() {
+    let _ = pretty_env_logger::try_init();
+
+    let data = std::fs::read(TEST_FILE_NAME).unwrap();
+    let reader = ShiftedReader {
+        inner: std::io::Cursor::new(&data),
+        base: 5 * 1024 * 1024 * 1024,
+    };
+    let shortcut = ShellLink::from_reader(reader, WINDOWS_1252).unwrap();
+    assert_eq!(
+        shortcut.link_info().as_ref().unwrap().local_base_path(),
+        Some(r"C:\test\a.txt")
+    );
+}
+
+#[test]
+fn test_link_in_larger_stream() {
+    let _ = pretty_env_logger::try_init();
+
+    // a link which has been carved from a disk image, which is larger than
+    // the maximum size of a link
+    let link = std::fs::read(TEST_FILE_NAME).unwrap();
+    let mut image = vec![0xffu8; 100];
+    image.extend_from_slice(&link);
+    image.resize(image.len() + 20 * 1024 * 1024, 0);
+
+    let mut reader = std::io::Cursor::new(&image);
+    reader.set_position(100);
+    let shortcut = ShellLink::from_reader(reader, WINDOWS_1252).unwrap();
+    assert_eq!(
+        shortcut.link_target(),
+        ShellLink::from_bytes(&link, WINDOWS_1252)
+            .unwrap()
+            .link_target()
+    );
+
+    // the trailing data end at the maximum size
+    let trailing_data = shortcut.trailing_data().unwrap();
+    assert_eq!(*trailing_data.offset(), 100 + link.len() as u64);
+    assert_eq!(
+        trailing_data.len() as u64,
+        ParseLimits::default().max_size - link.len() as u64
+    );
+}
+
+/// returns the bytes of the test file, with `block` appended to the
+/// ExtraData section
+fn test_file_with_block(signature: u32, block_data: &[u8]) -> Vec<u8> {
+    let mut data = std::fs::read(TEST_FILE_NAME).unwrap();
+    append_block(&mut data, signature, block_data);
+    data
+}
+
+/// appends `block` to the ExtraData section of the shell link `data`
+fn append_block(data: &mut Vec<u8>, signature: u32, block_data: &[u8]) {
+    // remove the terminal block
+    data.truncate(data.len() - 4);
+    data.extend_from_slice(&(block_data.len() as u32 + 8).to_le_bytes());
+    data.extend_from_slice(&signature.to_le_bytes());
+    data.extend_from_slice(block_data);
+    data.extend_from_slice(&0u32.to_le_bytes());
+}
+
+#[test]
+fn test_codepage_from_console_fe_data_block() {
+    let _ = pretty_env_logger::try_init();
+
+    // ConsoleFEDataBlock with the OEM code page of Russian systems
+    let mut data = test_file_with_block(0xa0000004, &866u32.to_le_bytes());
+    // "C:\test\" followed by a Cyrillic letter in code page 1251
+    data[0x140] = 0xc0;
+
+    // the code page passed by the caller is used by default
+    let shortcut = ShellLink::from_bytes(&data, WINDOWS_1252).unwrap();
+    assert_eq!(shortcut.default_codepage(), WINDOWS_1252);
+    assert_eq!(
+        shortcut.link_info().as_ref().unwrap().local_base_path(),
+        Some("C:\\test\\À.txt")
+    );
+
+    let options = ParseOptions {
+        codepage_selection: CodepageSelection::FromLink,
+        ..Default::default()
+    };
+    let shortcut = ShellLink::from_bytes_with_options(&data, WINDOWS_1252, options).unwrap();
+    assert_eq!(shortcut.default_codepage(), encoding_rs::WINDOWS_1251);
+    assert_eq!(
+        shortcut.link_info().as_ref().unwrap().local_base_path(),
+        Some("C:\\test\\А.txt")
+    );
+}
+
+#[test]
+fn test_guess_codepage() {
+    let _ = pretty_env_logger::try_init();
+
+    // EnvironmentVariableDataBlock, which contains a path both as ANSI
+    // string in code page 1251 and as Unicode string
+    let target = "%USERPROFILE%\\Рабочий стол";
+    let mut block = vec![0u8; 260 + 520];
+    let (ansi, _, _) = encoding_rs::WINDOWS_1251.encode(target);
+    block[..ansi.len()].copy_from_slice(&ansi);
+    for (i, c) in target.encode_utf16().enumerate() {
+        block[260 + 2 * i..260 + 2 * i + 2].copy_from_slice(&c.to_le_bytes());
+    }
+    let mut data = test_file_with_block(0xa0000001, &block);
+
+    // IconEnvironmentDataBlock, whose ANSI string contains characters which
+    // have been replaced by '?', because they are not part of code page 1251
+    let icon = "%USERPROFILE%\\日本.ico";
+    let icon_ansi = b"%USERPROFILE%\\??.ico";
+    let mut block = vec![0u8; 260 + 520];
+    block[..icon_ansi.len()].copy_from_slice(icon_ansi);
+    for (i, c) in icon.encode_utf16().enumerate() {
+        block[260 + 2 * i..260 + 2 * i + 2].copy_from_slice(&c.to_le_bytes());
+    }
+    append_block(&mut data, 0xa0000007, &block);
+
+    let shortcut = ShellLink::from_bytes(&data, WINDOWS_1252).unwrap();
+    assert_eq!(shortcut.default_codepage(), WINDOWS_1252);
+
+    let options = ParseOptions {
+        codepage_selection: CodepageSelection::Guess,
+        ..Default::default()
+    };
+    let shortcut = ShellLink::from_bytes_with_options(&data, WINDOWS_1252, options).unwrap();
+    assert_eq!(shortcut.default_codepage(), encoding_rs::WINDOWS_1251);
+    let block = shortcut.extra_data().environment_variables().unwrap();
+    assert_eq!(block.target_ansi(), target);
+}
+
+#[test]
+fn test_shell_link_builder() {
+    let _ = pretty_env_logger::try_init();
+
+    let link = ShellLink::builder()
+        .target(r"C:\Windows\System32\cmd.exe")
+        .environment_target(r"%windir%\System32\cmd.exe")
+        .arguments("/k ver")
+        .working_dir(r"C:\Users\Public")
+        .description("Command Prompt")
+        .icon(r"%windir%\System32\cmd.exe", 0)
+        .show_command(ShowCommand::ShowMaximized)
+        .console(lnk::extradata::console_data::ConsoleDataBlock::default())
+        .build()
+        .unwrap();
+
+    assert_eq!(
+        link.link_target().as_deref(),
+        Some(r"C:\Windows\System32\cmd.exe")
+    );
+    assert_eq!(
+        *link.header().link_flags(),
+        LinkFlags::IS_UNICODE
+            | LinkFlags::HAS_LINK_TARGET_ID_LIST
+            | LinkFlags::HAS_LINK_INFO
+            | LinkFlags::HAS_ARGUMENTS
+            | LinkFlags::HAS_WORKING_DIR
+            | LinkFlags::HAS_NAME
+            | LinkFlags::HAS_ICON_LOCATION
+            | LinkFlags::HAS_EXP_STRING
+    );
+    assert_eq!(*link.header().show_command(), ShowCommand::ShowMaximized);
+    assert_eq!(
+        link.extra_data()
+            .environment_variables()
+            .unwrap()
+            .target_ansi(),
+        r"%windir%\System32\cmd.exe"
+    );
+    assert_eq!(link.extra_data().console().unwrap().face_name(), "Consolas");
+}
+
+#[test]
+fn test_shell_link_builder_errors() {
+    assert_eq!(
+        ShellLink::builder()
+            .arguments("--help")
+            .build()
+            .unwrap_err(),
+        BuildError::MissingTarget
+    );
+    assert_eq!(
+        ShellLink::builder()
+            .target(r"Windows\notepad.exe")
+            .build()
+            .unwrap_err(),
+        BuildError::InvalidTargetPath(r"Windows\notepad.exe".to_string())
+    );
+    assert_eq!(
+        ShellLink::builder()
+            .target(r"C:\Windows\notepad.exe")
+            .arguments("x".repeat(65536))
+            .build()
+            .unwrap_err(),
+        BuildError::StringTooLong {
+            field: "Arguments",
+            length: 65536,
+            max: 65535
+        }
+    );
+    assert_eq!(
+        ShellLink::builder()
+            .environment_target(format!(r"%USERPROFILE%\{}", "x".repeat(250)))
+            .build()
+            .unwrap_err(),
+        BuildError::StringTooLong {
+            field: "EnvironmentTarget",
+            length: 264,
+            max: 259
+        }
+    );
+    assert_eq!(
+        ShellLink::builder()
+            .target(r"C:\Windows\notepad.exe")
+            .description("Блокнот")
+            .unicode(false)
+            .build()
+            .unwrap_err(),
+        BuildError::NotEncodable {
+            field: "Description",
+            codepage: "windows-1252"
+        }
+    );
+
+    // the ANSI target of the EnvironmentVariableDataBlock is always written
+    assert_eq!(
+        ShellLink::builder()
+            .environment_target("日".repeat(200))
+            .default_codepage(encoding_rs::SHIFT_JIS)
+            .build()
+            .unwrap_err(),
+        BuildError::StringTooLong {
+            field: "EnvironmentTarget",
+            length: 400,
+            max: 259
+        }
+    );
+    let link = ShellLink::builder()
+        .environment_target(r"%USERPROFILE%\Рабочий стол\x.exe")
+        .build()
+        .unwrap();
+    let Some(extradata::ExtraDataBlock::EnvironmentProps(environment_props)) =
+        link.extra_data().blocks().first()
+    else {
+        panic!("missing EnvironmentVariableDataBlock");
+    };
+    assert_eq!(
+        environment_props.target_ansi(),
+        r"%USERPROFILE%\??????? ????\x.exe"
+    );
+    assert_eq!(
+        environment_props.target_unicode().as_deref(),
+        Some(r"%USERPROFILE%\Рабочий стол\x.exe")
+    );
+
+    // with Unicode, the ANSI path of the LinkInfo falls back to '?'
+    let link = ShellLink::builder()
+        .target(r"C:\Блокнот.exe")
+        .build()
+        .unwrap();
+    let link_info = link.link_info().as_ref().unwrap();
+    assert_eq!(link_info.local_base_path(), Some(r"C:\???????.exe"));
+    assert_eq!(link.link_target().as_deref(), Some(r"C:\Блокнот.exe"));
+}
+
+#[test]
+fn test_id_list_from_windows_path() {
+    let _ = pretty_env_logger::try_init();
+
+    let id_list = IdList::from_windows_path(r"C:\Program Files\Notepad++\notepad++.exe").unwrap();
+    assert_eq!(
+        id_list.path().as_deref(),
+        Some(r"C:\Program Files\Notepad++\notepad++.exe")
+    );
+
+    // the My Computer and drive items are identical to those created by Windows
+    let shortcut = ShellLink::open(TEST_FILE_NAME, WINDOWS_1252).unwrap();
+    let native = shortcut.linktarget_id_list().as_ref().unwrap().id_list();
+    let items = id_list.item_id_list();
+    assert_eq!(items.len(), 5);
+    assert_eq!(items[0].data(), native[0].data());
+    assert_eq!(items[1].data(), native[1].data());
+
+    let entries: Vec<_> = items[2..]
+        .iter()
+        .map(|item| match item.shell_item() {
+            ShellItem::FileEntry(entry) => entry,
+            other => panic!("unexpected shell item {other:?}"),
+        })
+        .collect();
+    assert!(entries[0].is_directory());
+    assert_eq!(entries[0].primary_name(), "PROGRA~1");
+    assert_eq!(entries[0].long_name(), Some("Program Files"));
+    assert_eq!(
+        *entries[0].file_attributes(),
+        FileAttributeFlags::FILE_ATTRIBUTE_DIRECTORY
+    );
+    assert_eq!(entries[1].primary_name(), "NOTEPA~1");
+    assert!(entries[2].is_file());
+    assert_eq!(entries[2].primary_name(), "NOTEPA~1.EXE");
+    assert_eq!(entries[2].long_name(), Some("notepad++.exe"));
+    assert_eq!(entries[2].file_entry_extension().unwrap().version(), &9);
+
+    // a trailing backslash denotes a directory
+    let id_list = IdList::from_windows_path(r"d:\Users\").unwrap();
+    assert_eq!(id_list.path().as_deref(), Some(r"D:\Users"));
+    match id_list.item_id_list()[2].shell_item() {
+        ShellItem::FileEntry(entry) => assert!(entry.is_directory()),
+        other => panic!("unexpected shell item {other:?}"),
+    }
+    assert_eq!(
+        IdList::from_windows_path(r"C:\")
+            .unwrap()
+            .item_id_list()
+            .len(),
+        2
+    );
+
+    for path in [
+        r"Windows\notepad.exe",
+        r"C:\Windows\\notepad.exe",
+        r"C:\a?.txt",
+        "C:/Windows",
+    ] {
+        assert_eq!(
+            IdList::from_windows_path(path).unwrap_err(),
+            BuildError::InvalidTargetPath(path.to_string())
+        );
+    }
+}
+
+#[test]
+fn test_new_for_windows_path() {
+    let _ = pretty_env_logger::try_init();
+
+    let modified = NaiveDate::from_ymd_opt(2024, 5, 17)
+        .unwrap()
+        .and_hms_opt(9, 30, 12)
+        .unwrap();
+    let mut metadata = TargetMetadata::file();
+    metadata
+        .set_file_size(0x1_0000_2000)
+        .set_write_time(FileTime::from(modified));
+
+    let link =
+        ShellLink::new_for_windows_path(r"C:\Program Files\App\app.exe", Some(metadata)).unwrap();
+    assert_eq!(
+        link.link_target().as_deref(),
+        Some(r"C:\Program Files\App\app.exe")
+    );
+    assert_eq!(
+        link.id_list_target().as_deref(),
+        Some(r"C:\Program Files\App\app.exe")
+    );
+    assert_eq!(
+        link.string_data().working_dir().as_deref(),
+        Some(r"C:\Program Files\App")
+    );
+    assert_eq!(*link.header().file_size(), 0x2000);
+    assert_eq!(link.header().write_time().datetime(), modified);
+    assert_eq!(
+        *link.header().file_attributes(),
+        FileAttributeFlags::FILE_ATTRIBUTE_ARCHIVE
+    );
+    let volume_id = link
+        .link_info()
+        .as_ref()
+        .and_then(|info| info.volume_id().as_ref())
+        .unwrap();
+    assert_eq!(*volume_id.drive_type(), linkinfo::DriveType::DriveFixed);
+
+    let items = link.linktarget_id_list().as_ref().unwrap().id_list();
+    match items.last().unwrap().shell_item() {
+        ShellItem::FileEntry(entry) => {
+            assert!(entry.is_file());
+            assert_eq!(*entry.file_size(), 0x2000);
+            assert_eq!(entry.modification_time().datetime(), Some(modified));
+        }
+        other => panic!("unexpected shell item {other:?}"),
+    }
+
+    // without metadata, a trailing backslash denotes a directory
+    let link = ShellLink::new_for_windows_path(r"D:\Data\", None).unwrap();
+    assert_eq!(link.link_target().as_deref(), Some(r"D:\Data"));
+    assert_eq!(
+        *link.header().file_attributes(),
+        FileAttributeFlags::FILE_ATTRIBUTE_DIRECTORY
+    );
+    assert_eq!(link.string_data().working_dir(), &None);
+
+    let link = ShellLink::new_for_windows_path(r"C:\setup.exe", None).unwrap();
+    assert_eq!(link.string_data().working_dir().as_deref(), Some(r"C:\"));
+
+    assert_eq!(
+        ShellLink::new_for_windows_path("/usr/bin/app", None).unwrap_err(),
+        BuildError::InvalidTargetPath("/usr/bin/app".to_string())
+    );
+}
+
+#[test]
+fn test_network_link() {
+    let _ = pretty_env_logger::try_init();
+
+    let link = ShellLink::new_for_windows_path(r"\\dfs\software\App\setup.exe", None).unwrap();
+    assert_eq!(
+        link.link_target().as_deref(),
+        Some(r"\\dfs\software\App\setup.exe")
+    );
+    assert!(link.linktarget_id_list().is_none());
+    assert_eq!(
+        link.string_data().working_dir().as_deref(),
+        Some(r"\\dfs\software\App")
+    );
+
+    let link_info = link.link_info().as_ref().unwrap();
+    assert_eq!(
+        *link_info.link_info_flags(),
+        linkinfo::LinkInfoFlags::COMMON_NETWORK_RELATIVE_LINK_AND_PATH_SUFFIX
+    );
+    assert_eq!(link_info.common_path_suffix(), r"App\setup.exe");
+    let network_link = link_info.common_network_relative_link().as_ref().unwrap();
+    assert_eq!(network_link.net_name(), r"\\dfs\software");
+    assert_eq!(
+        *network_link.network_provider_type(),
+        Some(linkinfo::NetworkProviderType::Smb)
+    );
+    assert_eq!(
+        *network_link.flags(),
+        linkinfo::CommonNetworkRelativeLinkFlags::VALID_NET_TYPE
+    );
+
+    // a mapped share is resolved using its device name
+    let link = ShellLink::builder()
+        .target(r"\\fileserver\Документы\report.docx")
+        .network_device("Z:")
+        .build()
+        .unwrap();
+    assert_eq!(link.link_target().as_deref(), Some(r"Z:\report.docx"));
+    let network_link = link
+        .link_info()
+        .as_ref()
+        .and_then(|info| info.common_network_relative_link().as_ref())
+        .unwrap();
+    assert_eq!(network_link.net_name(), r"\\fileserver\?????????");
+    assert_eq!(
+        network_link.net_name_unicode().as_deref(),
+        Some(r"\\fileserver\Документы")
+    );
+    assert_eq!(network_link.device_name().as_deref(), Some("Z:"));
+    assert!(network_link.flags().has_valid_device());
+
+    for path in [r"\\server", r"\\server\", r"\\server\share\\file.txt"] {
+        assert_eq!(
+            ShellLink::new_for_windows_path(path, None).unwrap_err(),
+            BuildError::InvalidTargetPath(path.to_string())
+        );
+    }
+
+    // the net name is always present, even if no flag is set
+    let network_link = linkinfo::CommonNetworkRelativeLink::new(r"\\server\share", None);
+    assert!(network_link.flags().is_empty());
+    let mut link = ShellLink::default();
+    link.set_link_info(Some(LinkInfo::new_network(network_link, "a.txt", None)));
+    assert_eq!(link.link_target().as_deref(), Some(r"\\server\share\a.txt"));
+}
+
+#[test]
+fn test_header_time_setters() {
+    let mut header = ShellLinkHeader::default();
+    let modified = NaiveDate::from_ymd_opt(2024, 5, 17)
+        .unwrap()
+        .and_hms_opt(9, 30, 12)
+        .unwrap();
+    header
+        .set_creation_time(modified.and_utc())
+        .set_access_time(std::time::SystemTime::UNIX_EPOCH)
+        .set_write_time(modified);
+    assert_eq!(header.creation_time().datetime(), modified);
+    assert_eq!(header.write_time(), header.creation_time());
+    assert_eq!(
+        header.access_time().raw(),
+        116_444_736_000_000_000,
+        "the Unix epoch as FILETIME"
+    );
+    assert!(header.access_time() < header.write_time());
+
+    header.set_write_time(0);
+    assert!(header.write_time().is_zero());
+}